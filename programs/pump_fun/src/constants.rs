@@ -6,7 +6,25 @@ pub const APP_STATS_SEED: &[u8] = b"app-stats";
 pub const AUTHORITY_SEED: &[u8] = b"authority";
 pub const TOKEN_CREATE_SEED: &[u8] = b"token-create";
 pub const TOKEN_ACCOUNT_SEED: &[u8] = b"token-account";
+pub const QUOTE_ACCOUNT_SEED: &[u8] = b"quote-account";
+pub const UNWRAP_ACCOUNT_SEED: &[u8] = b"unwrap-account";
 pub const SWAP_PAIR_SEED: &[u8] = b"swap-pair";
+pub const GOVERNANCE_SEED: &[u8] = b"governance";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const CURVE_PRESET_SEED: &[u8] = b"curve-preset";
+pub const PRICE_OBSERVATION_SEED: &[u8] = b"price-observation";
+/// namespace of the launchpad's own app state, its owner can open further namespaces
+pub const ROOT_NAMESPACE: u64 = 0;
 pub const FEE_PERCENTAGE: u16 = 100;
 pub const DENOMINATOR: u16 = 10000;
+pub const DEFAULT_GRADUATION_THRESHOLD: u64 = 85_000_000_000;
+pub const MAX_GOVERNANCE_SIGNERS: usize = 10;
+pub const MAX_QUOTE_MINTS: usize = 8;
+pub const MAX_HOOK_PROGRAMS: usize = 8;
+/// depth table levels that fit the 1024 byte return data of a view
+pub const MAX_DEPTH_LEVELS: usize = 42;
+/// price observations kept per pair, older ones are overwritten
+pub const OBSERVATION_CAPACITY: usize = 64;
+/// decimals of the quote unit curve parameters and thresholds are written in (wsol)
+pub const CURVE_QUOTE_DECIMALS: u8 = 9;
 
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::error::SwapError;
+
+/// Why a curve calculation failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveError {
+    /// An intermediate value overflowed or underflowed
+    Overflow,
+    /// A curve parameter makes the math undefined, e.g. a zero denominator
+    InvalidParameters,
+    /// The reserve the trade pays out of is empty
+    InsufficientLiquidity,
+    /// The trade is too small to move a single base unit out of the curve
+    ZeroOutput,
+    /// The collateral reserve is below what the stored curve position requires
+    InconsistentPosition,
+}
+
+pub type CurveResult<T> = std::result::Result<T, CurveError>;
+
+impl From<CurveError> for SwapError {
+    fn from(e: CurveError) -> Self {
+        match e {
+            CurveError::Overflow => SwapError::CalculationFailure,
+            CurveError::InvalidParameters => SwapError::InvalidCurve,
+            CurveError::InsufficientLiquidity => SwapError::InsufficientLiquidity,
+            CurveError::ZeroOutput => SwapError::ZeroTradingTokens,
+            CurveError::InconsistentPosition => SwapError::InconsistentReserves,
+        }
+    }
+}
+
+impl From<CurveError> for Error {
+    fn from(e: CurveError) -> Self {
+        SwapError::from(e).into()
+    }
+}
+
+/// Turns the `None` of a checked DFSPreciseNumber / integer operation into `CurveError::Overflow`
+pub(crate) trait OrOverflow<T> {
+    fn or_overflow(self) -> CurveResult<T>;
+}
+
+impl<T> OrOverflow<T> for Option<T> {
+    fn or_overflow(self) -> CurveResult<T> {
+        self.ok_or(CurveError::Overflow)
+    }
+}
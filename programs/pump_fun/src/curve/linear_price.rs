@@ -1,8 +1,17 @@
 use anchor_lang::prelude::*;
 
-use crate::dfs_precise_number::DFSPreciseNumber;
+use super::{ CurveError, CurveResult, OrOverflow };
+use crate::{ dfs_precise_number::{ DFSPreciseNumber, Rounding }, error::SwapError, TradeSide, DENOMINATOR };
+
+/// Price point of a depth table
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq)]
+pub struct DepthLevel {
+    pub tokens_sold: u64,
+    /// quote base units per whole token
+    pub price: u128,
+}
 
-#[derive(AnchorDeserialize, AnchorSerialize, Clone)]
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq)]
 pub struct LinearPriceCurve {
     /// Slope of price increase (how much price of token B increases for every token A that's bonded to it) numerator
     pub slope_numerator: u64,
@@ -22,75 +31,99 @@ fn solve_quadratic_positive_root(
   e_value_numerator: &DFSPreciseNumber,
   e_value_denominator: &DFSPreciseNumber,
   lhs_value: &DFSPreciseNumber,
-  should_round_sqrt_up: bool,
-) -> Option<DFSPreciseNumber> {
+  rounding: Rounding,
+) -> CurveResult<DFSPreciseNumber> {
   // solve positive root of 0 = k*x^2 + e*x + c, where c == -lhs_value
   // => x = (-e + sqrt(e^2 - 4kc)) / 2k
   // => x = (sqrt(e^2 + 4*k*lhs) - e) / 2k
+  // x grows with the sqrt term and shrinks with e, so the sqrt side is rounded with `rounding`
+  // and e the opposite way, which keeps every step on the same side of the exact root
 
   // k * 4 * lhs
   let four_k_lhs = k_numerator
-      .checked_mul(&(DFSPreciseNumber::new(4)?))?
-      .checked_mul(lhs_value)?
-      .checked_div(k_denominator)?;
+      .checked_mul(&(DFSPreciseNumber::new(4).or_overflow()?), rounding).or_overflow()?
+      .checked_mul(lhs_value, rounding).or_overflow()?
+      .checked_div(k_denominator, rounding).or_overflow()?;
 
   // e^2 + k * 4 * lhs
   let e2_plus_4_k_lhs = e_value_numerator
-      .checked_mul(e_value_numerator)?
-      .checked_div(e_value_denominator)?
-      .checked_div(&e_value_denominator)?
-      .checked_add(&four_k_lhs)?;
+      .checked_mul(e_value_numerator, rounding).or_overflow()?
+      .checked_div(e_value_denominator, rounding).or_overflow()?
+      .checked_div(e_value_denominator, rounding).or_overflow()?
+      .checked_add(&four_k_lhs).or_overflow()?;
 
-  // note we have to use u64 sqrt below (~10K compute) since PreciseNumber::sqrt (~100K compute)
-  // and u128 sqrt (~50K compute) are both too expensive
-  let sqrt_e2_plus_4_k_lhs = e2_plus_4_k_lhs.sqrt_u64(should_round_sqrt_up)?;
+  // exact to the last decimal, so the only rounding left is the direction the caller picks
+  // (sqrt_u64 is cheaper but only keeps ~9 significant digits)
+  let sqrt_e2_plus_4_k_lhs = e2_plus_4_k_lhs.sqrt(rounding).or_overflow()?;
 
   // numerator is sqrt(e^2 + 4*k*lhs) - e
-  let e_value = e_value_numerator.checked_div(e_value_denominator)?;
-  // due to sqrt rounding, sometimes this None's if we rounded down the sqrt, so treat that as 0
+  let e_value = e_value_numerator
+      .checked_div(e_value_denominator, rounding.opposite())
+      .or_overflow()?;
+  // e^2 and e are rounded apart, so for tiny lhs this can dip below 0, which is a root of 0
   let numerator = match sqrt_e2_plus_4_k_lhs.checked_sub(&e_value) {
       Some(val) => val,
-      None => DFSPreciseNumber::new(0)?,
+      None => DFSPreciseNumber::new(0).or_overflow()?,
   };
 
   // finally we return (sqrt(e^2-4kc) - e)/2k,
   // AKA numerator * k_denominator / k_numerator / 2 (do all the division last)
   numerator
-      .checked_mul(k_denominator)?
-      .checked_div(&k_numerator)?
-      .checked_div(&(DFSPreciseNumber::new(2)?))
+      .checked_mul(k_denominator, rounding).or_overflow()?
+      .checked_div(k_numerator, rounding).or_overflow()?
+      .checked_div(&(DFSPreciseNumber::new(2).or_overflow()?), rounding)
+      .or_overflow()
 }
 
 impl LinearPriceCurve {
+    /// Zero denominators leave the curve undefined and a zero slope can't be solved for b,
+    /// so every calculation checks these first to tell them apart from overflows
+    fn check_parameters(&self) -> CurveResult<()> {
+        if self.slope_numerator == 0
+            || self.slope_denominator == 0
+            || self.initial_token_a_price_denominator == 0
+        {
+            return Err(CurveError::InvalidParameters);
+        }
+        Ok(())
+    }
+
+    /// Token A locked at `b_value`, every step rounded with `rounding`
+    /// (the integral only grows with each term, so the result stays on that side of the exact value)
     fn amt_a_locked_at_b_value_quadratic(
         &self,
-        b_value: &DFSPreciseNumber
-    ) -> Option<DFSPreciseNumber> {
+        b_value: &DFSPreciseNumber,
+        rounding: Rounding
+    ) -> CurveResult<DFSPreciseNumber> {
         // The liquidity integral is `token_a_bonded = 0.5m*b^2 + a0*b + 0` (integration constant is 0 since we know
         // there's 0 token A bonded at b = 0)
 
         // 0.5 * m * b^2
-        let half_m_b_squared = DFSPreciseNumber::new(self.slope_numerator.into())?
-            .checked_mul(b_value)?
-            .checked_mul(b_value)?
-            .checked_div(&DFSPreciseNumber::new(self.slope_denominator.into())?)?
-            .checked_div(&DFSPreciseNumber::new(2)?)?;
+        let half_m_b_squared = DFSPreciseNumber::new(self.slope_numerator.into()).or_overflow()?
+            .checked_mul(b_value, rounding).or_overflow()?
+            .checked_mul(b_value, rounding).or_overflow()?
+            .checked_div(&DFSPreciseNumber::new(self.slope_denominator.into()).or_overflow()?, rounding).or_overflow()?
+            .checked_div(&DFSPreciseNumber::new(2).or_overflow()?, rounding).or_overflow()?;
 
         // a0 * b (note a0 and b are always positive) - make sure to do division last
-        let a0_times_b = DFSPreciseNumber::new(self.initial_token_a_price_numerator.into())?
-            .checked_mul(b_value)?
-            .checked_div(&DFSPreciseNumber::new(self.initial_token_a_price_denominator.into())?)?;
-
-        half_m_b_squared.checked_add(&a0_times_b)
+        let a0_times_b = DFSPreciseNumber::new(self.initial_token_a_price_numerator.into()).or_overflow()?
+            .checked_mul(b_value, rounding).or_overflow()?
+            .checked_div(
+                &DFSPreciseNumber::new(self.initial_token_a_price_denominator.into()).or_overflow()?,
+                rounding
+            ).or_overflow()?;
+
+        half_m_b_squared.checked_add(&a0_times_b).or_overflow()
     }
 
     /// Returns the positive root for token_a_amount = 0.5m*b^2 + a0*b + 0
-    /// (integration constant is always 0 since we know there's 0 token A bonded at b = 0)
+    /// (integration constant is always 0 since we know there's 0 token A bonded at b = 0),
+    /// rounded with `rounding`
     fn b_value_with_amt_a_locked_quadratic(
         &self,
         token_a_amount: &DFSPreciseNumber,
-        should_round_sqrt_up: bool
-    ) -> Option<DFSPreciseNumber> {
+        rounding: Rounding
+    ) -> CurveResult<DFSPreciseNumber> {
         // (We're using k/e for quadratic coefficients instead of a/b to not clash with token a/b names)
 
         // k = 0.5 * m
@@ -98,16 +131,21 @@ impl LinearPriceCurve {
         // precision (only 12 decimal digits max) - we're going to be multiplying it against prices (k*b^2) so
         // no need to lose that precision (and as long as slope_numerator/price are all u64 there's plenty of
         // room in PreciseNumber to avoid overflow)
-        let slope_numerator = DFSPreciseNumber::new(self.slope_numerator.into())?;
-        let slope_denominator = DFSPreciseNumber::new(self.slope_denominator.into())?;
-        let k_numerator = slope_numerator.checked_mul(&DFSPreciseNumber::new(1)?)?;
-        let k_denominator = slope_denominator.checked_mul(&DFSPreciseNumber::new(2)?)?;
+        // (whole number products, exact whichever way they're rounded)
+        let slope_numerator = DFSPreciseNumber::new(self.slope_numerator.into()).or_overflow()?;
+        let slope_denominator = DFSPreciseNumber::new(self.slope_denominator.into()).or_overflow()?;
+        let k_numerator = slope_numerator
+            .checked_mul(&DFSPreciseNumber::new(1).or_overflow()?, rounding)
+            .or_overflow()?;
+        let k_denominator = slope_denominator
+            .checked_mul(&DFSPreciseNumber::new(2).or_overflow()?, rounding)
+            .or_overflow()?;
 
         // e = a0
-        let e_value_numerator = DFSPreciseNumber::new(self.initial_token_a_price_numerator.into())?;
+        let e_value_numerator = DFSPreciseNumber::new(self.initial_token_a_price_numerator.into()).or_overflow()?;
         let e_value_denominator = DFSPreciseNumber::new(
             self.initial_token_a_price_denominator.into()
-        )?;
+        ).or_overflow()?;
 
         // solve 0 = k*x^2 + e*x - token_a_amount
         solve_quadratic_positive_root(
@@ -115,8 +153,8 @@ impl LinearPriceCurve {
             &k_denominator,
             &e_value_numerator,
             &e_value_denominator,
-            &token_a_amount,
-            should_round_sqrt_up
+            token_a_amount,
+            rounding
         )
     }
 
@@ -129,117 +167,267 @@ impl LinearPriceCurve {
         b_start: &DFSPreciseNumber,
         source_amount: u128,
         swap_destination_amount: u128
-    ) -> Option<(u128, u128)> {
+    ) -> CurveResult<Option<(u128, u128)>> {
         // if at b_start + swap_destination_amount (the maximum B that be given out by the swap),
         // then the A value is <= source_amount, so only take that amount of A instead and give them all the
         // Bs remaining
-        let maximum_b_value = b_start.checked_add(
-            &DFSPreciseNumber::new(swap_destination_amount)?
-        )?;
-        let maximum_a_locked = self.amt_a_locked_at_b_value_quadratic(&maximum_b_value)?;
-        let maximum_a_remaining = maximum_a_locked.checked_sub(&a_start)?.to_imprecise()?;
+        let maximum_b_value = b_start
+            .checked_add(&DFSPreciseNumber::new(swap_destination_amount).or_overflow()?)
+            .or_overflow()?;
+        // the buyer pays for the rest of the curve, so its cost is rounded up
+        let maximum_a_locked = self.amt_a_locked_at_b_value_quadratic(&maximum_b_value, Rounding::Up)?;
+        let maximum_a_remaining = maximum_a_locked
+            .checked_sub(a_start).or_overflow()?
+            .to_imprecise(Rounding::Up)
+            .or_overflow()?;
 
         if maximum_a_remaining <= source_amount {
-            return Some((maximum_a_remaining, swap_destination_amount));
+            Ok(Some((maximum_a_remaining, swap_destination_amount)))
         } else {
-            return None;
+            Ok(None)
+        }
+    }
+
+    /// Checks the curve parameters can price the full `supply` of bonded tokens.
+    /// Zero denominators or a zero slope make every swap fail, and a curve whose collateral
+    /// at full supply overflows u64 can never be fully bought, so both are rejected up front
+    pub fn validate(&self, supply: u64) -> Result<()> {
+        self.check_parameters().map_err(|_| SwapError::InvalidCurve)?;
+        require!(supply != 0, SwapError::InvalidCurve);
+        let supply = u128::from(supply);
+        let max_a_locked = DFSPreciseNumber::new(supply)
+            .or_overflow()
+            .and_then(|b_value| self.amt_a_locked_at_b_value_quadratic(&b_value, Rounding::Up))
+            .and_then(|a_locked| a_locked.to_imprecise(Rounding::Up).or_overflow())
+            .map_err(|_| SwapError::InvalidCurve)?;
+        require!(
+            max_a_locked > 0 && max_a_locked <= u128::from(u64::MAX),
+            SwapError::InvalidCurve
+        );
+        // buying the whole supply in one go and selling it all back must both resolve
+        let (_, bought) = self
+            .swap_a_to_b_at_position(max_a_locked, 0, 0, supply)
+            .map_err(|_| SwapError::InvalidCurve)?;
+        require!(bought == supply, SwapError::InvalidCurve);
+        self.swap_b_to_a_at_position(supply, supply, max_a_locked).map_err(|_| SwapError::InvalidCurve)?;
+        Ok(())
+    }
+
+    /// Rewrites the curve for a quote token with `to_decimals` decimals from parameters written
+    /// in a quote token with `from_decimals` decimals, so a preset prices the same in display
+    /// units whichever quote mint the pair uses
+    pub fn rescale(&self, from_decimals: u8, to_decimals: u8) -> Result<Self> {
+        let factor = decimals_factor(from_decimals, to_decimals)?;
+        let scale = |value: u64| value.checked_mul(factor).ok_or(SwapError::InvalidCurve);
+        let mut curve = self.clone();
+        if to_decimals >= from_decimals {
+            curve.slope_numerator = scale(self.slope_numerator)?;
+            curve.initial_token_a_price_numerator = scale(self.initial_token_a_price_numerator)?;
+        } else {
+            curve.slope_denominator = scale(self.slope_denominator)?;
+            curve.initial_token_a_price_denominator = scale(self.initial_token_a_price_denominator)?;
+        }
+        Ok(curve)
+    }
+
+    /// Marginal price at `b_value` times `token_amount`, i.e. `(m * b + a0) * token_amount`,
+    /// rounded with `rounding`
+    fn value_at_price(
+        &self,
+        b_value: u64,
+        token_amount: u128,
+        rounding: Rounding
+    ) -> CurveResult<DFSPreciseNumber> {
+        self.check_parameters()?;
+        let amount = DFSPreciseNumber::new(token_amount).or_overflow()?;
+        let m_b = DFSPreciseNumber::new(self.slope_numerator.into()).or_overflow()?
+            .checked_mul(&DFSPreciseNumber::new(b_value.into()).or_overflow()?, rounding).or_overflow()?
+            .checked_mul(&amount, rounding).or_overflow()?
+            .checked_div(&DFSPreciseNumber::new(self.slope_denominator.into()).or_overflow()?, rounding).or_overflow()?;
+        let a0 = DFSPreciseNumber::new(self.initial_token_a_price_numerator.into()).or_overflow()?
+            .checked_mul(&amount, rounding).or_overflow()?
+            .checked_div(
+                &DFSPreciseNumber::new(self.initial_token_a_price_denominator.into()).or_overflow()?,
+                rounding
+            ).or_overflow()?;
+        m_b.checked_add(&a0).or_overflow()
+    }
+
+    /// Price of one whole token B (`10^decimals` base units) in token A base units
+    /// once `b_value` tokens are bonded, i.e. `(m * b + a0) * 10^decimals`, rounded down
+    pub fn spot_price(&self, b_value: u64, decimals: u8) -> CurveResult<u128> {
+        let one_token = 10u128.checked_pow(u32::from(decimals)).or_overflow()?;
+        self.value_at_price(b_value, one_token, Rounding::Down)?
+            .to_imprecise(Rounding::Down)
+            .or_overflow()
+    }
+
+    /// Value of `token_amount` base units at the marginal price once `b_value` tokens are bonded,
+    /// in token A base units and rounded down. With the total supply this is the fully diluted
+    /// market cap, with the bonded tokens (`token_amount == b_value`) the circulating one
+    pub fn market_cap(&self, b_value: u64, token_amount: u64) -> CurveResult<u128> {
+        self.value_at_price(b_value, token_amount.into(), Rounding::Down)?
+            .to_imprecise(Rounding::Down)
+            .or_overflow()
+    }
+
+    /// Token A needed to buy the `remaining` tokens left on the curve once `b_value` are bonded,
+    /// before fees and rounded up
+    pub fn buyout_cost(&self, b_value: u64, remaining: u64) -> CurveResult<u128> {
+        self.check_parameters()?;
+        let b_start = DFSPreciseNumber::new(b_value.into()).or_overflow()?;
+        let b_end = b_start
+            .checked_add(&DFSPreciseNumber::new(remaining.into()).or_overflow()?)
+            .or_overflow()?;
+        // over-estimate the end and under-estimate the start so the cost is never short
+        self.amt_a_locked_at_b_value_quadratic(&b_end, Rounding::Up)?
+            .checked_sub(&self.amt_a_locked_at_b_value_quadratic(&b_start, Rounding::Down)?).or_overflow()?
+            .to_imprecise(Rounding::Up)
+            .or_overflow()
+    }
+
+    /// Change of the marginal price caused by trading `token_amount` from `b_value`, in basis
+    /// points of the price at `b_value`. The price is linear in b, so a buy of the amount and a sell
+    /// of the same amount move it equally, a sell can't exceed the `b_value` tokens bonded
+    pub fn price_impact_bps(&self, side: TradeSide, b_value: u64, token_amount: u64) -> CurveResult<u128> {
+        if side == TradeSide::Sell && token_amount > b_value {
+            return Err(CurveError::InsufficientLiquidity);
+        }
+        // informational, so the impact is rounded down, i.e. the price it's relative to up
+        let price = self.value_at_price(b_value, 1, Rounding::Up)?;
+        // a free token at b = 0 has no price to move relative to
+        if price.value.is_zero() {
+            return Err(CurveError::InvalidParameters);
+        }
+        DFSPreciseNumber::new(self.slope_numerator.into()).or_overflow()?
+            .checked_mul(&DFSPreciseNumber::new(token_amount.into()).or_overflow()?, Rounding::Down).or_overflow()?
+            .checked_mul(&DFSPreciseNumber::new(DENOMINATOR.into()).or_overflow()?, Rounding::Down).or_overflow()?
+            .checked_div(&DFSPreciseNumber::new(self.slope_denominator.into()).or_overflow()?, Rounding::Down).or_overflow()?
+            .checked_div(&price, Rounding::Down).or_overflow()?
+            .to_imprecise(Rounding::Down)
+            .or_overflow()
+    }
+
+    /// Spot price (see `spot_price`) after every `step_bps` of `supply` is sold,
+    /// from nothing bonded up to the full supply. The last level is always the full
+    /// supply, even when `step_bps` doesn't divide it evenly
+    pub fn depth_table(&self, supply: u64, step_bps: u16, decimals: u8) -> CurveResult<Vec<DepthLevel>> {
+        if step_bps == 0 || step_bps > DENOMINATOR {
+            return Err(CurveError::InvalidParameters);
         }
+        (0..=DENOMINATOR / step_bps)
+            .map(|level| level * step_bps)
+            .chain((DENOMINATOR / step_bps * step_bps < DENOMINATOR).then_some(DENOMINATOR))
+            .map(|sold_bps| {
+                let sold_bps = u128::from(sold_bps);
+                let tokens_sold = u64::try_from(u128::from(supply) * sold_bps / u128::from(DENOMINATOR))
+                    .map_err(|_| CurveError::Overflow)?;
+                Ok(DepthLevel {
+                    tokens_sold,
+                    price: self.spot_price(tokens_sold, decimals)?,
+                })
+            })
+            .collect()
     }
 
-    /// Swap's in user's collateral token and returns out the bonded token,
-    /// moving right on the price curve and increasing the price of the bonded token
-    pub fn swap_a_to_b(
+    /// Token A locked at the stored `position` (tokens bonded), rounded down and checked against
+    /// the `a_reserve` the pair actually holds. Swaps only ever leave the reserve at or above
+    /// the curve integral (up to rounding), so a reserve below it means the two are out of sync.
+    /// Rounding the start down under-estimates both what a buy starts from and what a sell pays out of
+    fn a_locked_at_position(
+        &self,
+        position: &DFSPreciseNumber,
+        a_reserve: u128
+    ) -> CurveResult<DFSPreciseNumber> {
+        let a_locked = self.amt_a_locked_at_b_value_quadratic(position, Rounding::Down)?;
+        let a_locked_floor = a_locked.to_imprecise(Rounding::Down).or_overflow()?;
+        if a_reserve < a_locked_floor {
+            return Err(CurveError::InconsistentPosition);
+        }
+        Ok(a_locked)
+    }
+
+    /// Swaps in the user's collateral token and returns out the bonded token, moving right on
+    /// the price curve from the stored `position` (tokens bonded). The start is known exactly
+    /// instead of being solved for from the collateral reserve, so only the end point needs a sqrt
+    pub fn swap_a_to_b_at_position(
         &self,
         source_amount: u128, // amount of user's token a (collateral token)
+        position: u128, // token b bonded so far
         swap_source_amount: u128, // swap's token a (collateral token)
         swap_destination_amount: u128 // swap's remaining token b (bonded token)
-    ) -> Option<(u128, u128)> {
-        // use swap_source_amount (collateral token) to determine where we are on the integration curve
-        // note this only works if non-init deposits are disabled (and maybe if the initial deposit didn't have any token A in it?),
-        // otherwise there could be some A token in the pool that isn't part of the bonding curve
-
-        // quadratic formula version:
-        let a_start = DFSPreciseNumber::new(swap_source_amount)?;
-
-        let b_start = self.b_value_with_amt_a_locked_quadratic(&a_start, true)?;
-
-        match
-            self.maximum_a_remaining_for_swap_a_to_b(
-                &a_start,
-                &b_start,
-                source_amount,
-                swap_destination_amount
-            )
-        {
-            Some(val) => {
-                return Some(val);
-            }
-            // no need to return None here if checked_add fails, can just skip this check and do real calculation below
-            None => (),
+    ) -> CurveResult<(u128, u128)> {
+        self.check_parameters()?;
+        // nothing left on the curve to sell
+        if swap_destination_amount == 0 {
+            return Err(CurveError::InsufficientLiquidity);
         }
 
-        // otherwise, there's enough B tokens for all the A they put in, find the b_end value for the amount of A
-        // they're putting in and give them `b_end - b_start` tokens out
-        let a_end = a_start.checked_add(&DFSPreciseNumber::new(source_amount)?)?;
+        let b_start = DFSPreciseNumber::new(position).or_overflow()?;
+        let a_start = self.a_locked_at_position(&b_start, swap_source_amount)?;
 
-        let b_end = self.b_value_with_amt_a_locked_quadratic(&a_end, false)?;
+        if let Some(val) = self.maximum_a_remaining_for_swap_a_to_b(
+            &a_start,
+            &b_start,
+            source_amount,
+            swap_destination_amount
+        )? {
+            return Ok(val);
+        }
+
+        let a_end = a_start.checked_add(&DFSPreciseNumber::new(source_amount).or_overflow()?).or_overflow()?;
 
-        let difference = b_end.checked_sub(&b_start)?;
-        // PreciseNumber rounds .5+ up by default, make sure to floor instead so we don't allow
-        // dust to round up for free
-        let destination_amount = difference.floor()?.to_imprecise()?;
+        // round the end point down so the user never gets a fraction of a token they didn't pay for
+        let b_end = self.b_value_with_amt_a_locked_quadratic(&a_end, Rounding::Down)?;
+
+        // b_end is rounded down, so for dust it can land below the exact b_start
+        let destination_amount = b_end
+            .checked_sub(&b_start).ok_or(CurveError::ZeroOutput)?
+            .to_imprecise(Rounding::Down)
+            .or_overflow()?;
+        if destination_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
 
-        Some((source_amount, destination_amount))
+        Ok((source_amount, destination_amount))
     }
 
-    pub fn swap_b_to_a(
+    /// Swaps in the bonded token and returns out the user's collateral token, moving left on
+    /// the price curve from the stored `position` (tokens bonded). Both ends of the trade are
+    /// on whole tokens, so the payout is the difference of the curve integral at the two points
+    /// and no sqrt is needed
+    pub fn swap_b_to_a_at_position(
         &self,
         source_amount: u128,
-        _swap_source_amount: u128,
+        position: u128,
         swap_destination_amount: u128
-    ) -> Option<(u128, u128)> {
-        // use swap_destination_amount (collateral token) to determine where we are on the integration curve
-        // note this only works if non-init deposits are disabled (and maybe if the initial deposit didn't have any token A in it?),
-        // otherwise there could be some A token in the pool that isn't part of the bonding curve
-
-        // make sure we round up here so that b_end and a_end are also over-estimated, which rounds down the final
-        // token a output
-        let b_start = self.b_value_with_amt_a_locked_quadratic(
-            &DFSPreciseNumber::new(swap_destination_amount)?,
-            true
-        )?;
-
-        // b_end can be negative if the user put in too many B tokens (handled below)
-        let (b_end, b_end_is_negative) = b_start.unsigned_sub(
-            &DFSPreciseNumber::new(source_amount)?
-        );
-
-        // make sure to use b_end.ceiling() when doing below calculations a_end so we don't round in favor of the user
-        // if we use b_end directly, it's possible to gain tokens for free by swapping back and forth due to
-        // rounding (see swap_large_price_a_u32 test)
-        // (especially since sqrt_babylonian under estimates, we often will end up with a b_end/a_end that's too low
-        // due to rounding)
-        let b_end = b_end.ceiling()?;
-
-        // if b_end < 0 (i.e. there aren't enough A tokens in the swap for all the B tokens they put in),
-        // then just give them all of the a tokens (swap_destination_amount) and only take the B tokens required to
-        // get down from b_start to 0. this only works if we assume 0 A locked at b = 0
-        if b_end_is_negative {
-            return Some((b_start.to_imprecise()?, swap_destination_amount));
+    ) -> CurveResult<(u128, u128)> {
+        self.check_parameters()?;
+        // no collateral bonded to pay out
+        if swap_destination_amount == 0 {
+            return Err(CurveError::InsufficientLiquidity);
         }
 
-        // otherwise if there's enough A tokens locked in swap_destination_amount, figure out the A value at
-        // b_end and give them the difference (swap_destination_amount - a_end) tokens
-        let a_end = self.amt_a_locked_at_b_value_quadratic(&b_end)?;
-
-        // PreciseNumber rounds .5+ up by default, make sure to floor instead so we don't allow
-        // dust to round up for free
-        let destination_amount = DFSPreciseNumber::new(swap_destination_amount)?
-            .checked_sub(&a_end)?
-            .floor()?
-            .to_imprecise()?;
+        let b_start = DFSPreciseNumber::new(position).or_overflow()?;
+        let a_start = self.a_locked_at_position(&b_start, swap_destination_amount)?;
+
+        // selling more than was ever bonded only takes the tokens back down to b = 0
+        let source_amount = source_amount.min(position);
+        let b_end = DFSPreciseNumber::new(position - source_amount).or_overflow()?;
+        // a_start is rounded down and a_end up, so the payout between them is under-estimated
+        let a_end = self.amt_a_locked_at_b_value_quadratic(&b_end, Rounding::Up)?;
+
+        // round down so dust never rounds up in the seller's favour, a_start is at most a fraction
+        // above the reserve so the payout always fits in it
+        let destination_amount = a_start
+            .checked_sub(&a_end).or_overflow()?
+            .to_imprecise(Rounding::Down)
+            .or_overflow()?;
+        if destination_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
 
-        Some((source_amount, destination_amount))
+        Ok((source_amount, destination_amount))
     }
 }
 
@@ -251,4 +439,206 @@ pub fn to_u128(val: u64) -> Result<u128> {
 pub fn to_u64(val: u128) -> Result<u64> {
     val.try_into()
         .map_err(|_| crate::error::SwapError::ConversionFailure.into())
-}
\ No newline at end of file
+}
+
+fn decimals_factor(from_decimals: u8, to_decimals: u8) -> Result<u64> {
+    10u64
+        .checked_pow(u32::from(from_decimals.abs_diff(to_decimals)))
+        .ok_or(SwapError::InvalidCurve.into())
+}
+
+/// Converts a quote amount between decimal bases, rounding down when decimals are dropped
+pub fn rescale_amount(amount: u64, from_decimals: u8, to_decimals: u8) -> Result<u64> {
+    let factor = decimals_factor(from_decimals, to_decimals)?;
+    if to_decimals >= from_decimals {
+        amount.checked_mul(factor).ok_or(SwapError::CalculationFailure.into())
+    } else {
+        Ok(amount / factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(
+        slope_numerator: u64,
+        slope_denominator: u64,
+        initial_token_a_price_numerator: u64,
+        initial_token_a_price_denominator: u64
+    ) -> LinearPriceCurve {
+        LinearPriceCurve {
+            slope_numerator,
+            slope_denominator,
+            initial_token_a_price_numerator,
+            initial_token_a_price_denominator,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        // 100k tokens with 6 decimals, ends at 30k sol locked
+        assert!(curve(1, 200000000, 150, 3).validate(100_000_000_000).is_ok());
+        // zero initial price is fine, the slope alone prices the curve
+        assert!(curve(1, 200000000, 0, 1).validate(100_000_000_000).is_ok());
+
+        // zero denominators, zero slope and zero supply are rejected
+        assert!(curve(1, 0, 150, 3).validate(100_000_000_000).is_err());
+        assert!(curve(1, 200000000, 150, 0).validate(100_000_000_000).is_err());
+        assert!(curve(0, 200000000, 150, 3).validate(100_000_000_000).is_err());
+        assert!(curve(1, 200000000, 150, 3).validate(0).is_err());
+
+        // full supply would need more than u64::MAX collateral
+        assert!(curve(u64::MAX, 1, 150, 3).validate(1_000_000_000_000_000_000).is_err());
+        assert!(curve(1, 1, u64::MAX, 1).validate(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_rescale() {
+        let wsol_curve = curve(1, 200000000, 150, 3);
+        // usdc has 6 decimals, every price is 1000x smaller in base units
+        let usdc_curve = wsol_curve.rescale(9, 6).unwrap();
+        assert_eq!(usdc_curve, curve(1, 200000000000, 150, 3000));
+        assert!(usdc_curve.validate(100_000_000_000).is_ok());
+        assert_eq!(usdc_curve.rescale(6, 9).unwrap(), curve(1000, 200000000000, 150000, 3000));
+        assert_eq!(wsol_curve.rescale(9, 9).unwrap(), wsol_curve);
+
+        // a full buy costs the same in display units on both quotes
+        let (wsol_in, _) = wsol_curve.swap_a_to_b_at_position(u64::MAX.into(), 0, 0, 100_000_000_000).unwrap();
+        let (usdc_in, _) = usdc_curve.swap_a_to_b_at_position(u64::MAX.into(), 0, 0, 100_000_000_000).unwrap();
+        assert!(wsol_in / 1000 - usdc_in <= 1);
+
+        assert!(curve(u64::MAX, 1, 150, 3).rescale(6, 9).is_err());
+        assert!(wsol_curve.rescale(0, 30).is_err());
+
+        assert_eq!(rescale_amount(85_000_000_000, 9, 6).unwrap(), 85_000_000);
+        assert_eq!(rescale_amount(85_000_000, 6, 9).unwrap(), 85_000_000_000);
+        assert_eq!(rescale_amount(1, 9, 6).unwrap(), 0);
+        assert!(rescale_amount(u64::MAX, 6, 9).is_err());
+    }
+
+    #[test]
+    fn test_spot_price() {
+        let curve = curve(1, 200000000, 150, 3);
+        // 50 base units per token base unit at launch, for a whole 6 decimal token
+        assert_eq!(curve.spot_price(0, 6).unwrap(), 50_000_000);
+        // every 2e8 tokens bonded adds one base unit per token base unit
+        assert_eq!(curve.spot_price(200_000_000, 6).unwrap(), 51_000_000);
+        assert_eq!(curve.spot_price(100_000_000, 0).unwrap(), 50);
+        assert_eq!(curve.spot_price(0, 40), Err(CurveError::Overflow));
+    }
+
+    #[test]
+    fn test_market_helpers() {
+        let curve = curve(1, 200000000, 150, 3);
+        // 1e9 tokens at 50 + 5 = 55 base units each
+        assert_eq!(curve.market_cap(1_000_000_000, 1_000_000_000).unwrap(), 55_000_000_000);
+        assert_eq!(curve.market_cap(0, 1_000_000_000).unwrap(), 50_000_000_000);
+
+        // buying out the whole supply costs what a full buy charges
+        let supply = 100_000_000_000u64;
+        let cost = curve.buyout_cost(0, supply).unwrap();
+        let (full_buy, bought) = curve.swap_a_to_b_at_position(u64::MAX.into(), 0, 0, supply.into()).unwrap();
+        assert_eq!(bought, u128::from(supply));
+        assert!(cost.abs_diff(full_buy) <= 1);
+        // and the two halves add up to it
+        let half = supply / 2;
+        let halves = curve.buyout_cost(0, half).unwrap() + curve.buyout_cost(half, supply - half).unwrap();
+        assert!(halves.abs_diff(cost) <= 1);
+
+        // 1e9 tokens move the price by 5 on 50
+        assert_eq!(curve.price_impact_bps(TradeSide::Buy, 0, 1_000_000_000).unwrap(), 1000);
+        assert_eq!(curve.price_impact_bps(TradeSide::Buy, 1_000_000_000, 0).unwrap(), 0);
+        // selling them back moves it as much, relative to the price they were sold at
+        assert_eq!(curve.price_impact_bps(TradeSide::Sell, 1_000_000_000, 1_000_000_000).unwrap(), 909);
+        // but no more than was bonded can be sold
+        assert_eq!(
+            curve.price_impact_bps(TradeSide::Sell, 1_000_000_000, 1_000_000_001),
+            Err(CurveError::InsufficientLiquidity)
+        );
+    }
+
+    #[test]
+    fn test_depth_table() {
+        let curve = curve(1, 200000000, 150, 3);
+        let table = curve.depth_table(1_000_000_000, 2500, 6).unwrap();
+        assert_eq!(table.len(), 5);
+        assert_eq!(table[0], DepthLevel { tokens_sold: 0, price: 50_000_000 });
+        assert_eq!(table[2].tokens_sold, 500_000_000);
+        assert_eq!(table[2].price, 52_500_000);
+        assert_eq!(table[4], DepthLevel { tokens_sold: 1_000_000_000, price: 55_000_000 });
+        // 30% steps stop at 90%, the full supply is added as a last level
+        let table = curve.depth_table(1_000_000_000, 3000, 6).unwrap();
+        assert_eq!(table.len(), 5);
+        assert_eq!(table[3].tokens_sold, 900_000_000);
+        assert_eq!(table[4], DepthLevel { tokens_sold: 1_000_000_000, price: 55_000_000 });
+        assert_eq!(curve.depth_table(1_000_000_000, 10000, 6).unwrap().len(), 2);
+        assert_eq!(curve.depth_table(1_000_000_000, 0, 6), Err(CurveError::InvalidParameters));
+        assert_eq!(curve.depth_table(1_000_000_000, 10001, 6), Err(CurveError::InvalidParameters));
+    }
+
+    #[test]
+    fn test_swap_errors() {
+        assert_eq!(
+            curve(1, 0, 150, 3).swap_a_to_b_at_position(1_000, 0, 0, 1_000_000),
+            Err(CurveError::InvalidParameters)
+        );
+        let curve = curve(1, 200000000, 150, 3);
+        // one lamport can't buy a token at 50 lamports each, and selling nothing pays nothing
+        assert_eq!(curve.swap_a_to_b_at_position(1, 0, 0, 1_000_000), Err(CurveError::ZeroOutput));
+        assert_eq!(curve.swap_b_to_a_at_position(0, 1_000_000, 100_000_000), Err(CurveError::ZeroOutput));
+        // sold out curve and an empty quote vault
+        assert_eq!(
+            curve.swap_a_to_b_at_position(1_000, 1_000_000, 100_000_000, 0),
+            Err(CurveError::InsufficientLiquidity)
+        );
+        assert_eq!(curve.swap_b_to_a_at_position(1_000, 0, 0), Err(CurveError::InsufficientLiquidity));
+        assert!(matches!(
+            LinearPriceCurve { slope_numerator: u64::MAX, ..curve }
+                .market_cap(u64::MAX, u64::MAX)
+                .map_err(SwapError::from),
+            Err(SwapError::CalculationFailure)
+        ));
+    }
+
+    #[test]
+    fn test_swap_at_position() {
+        let curve = curve(1, 200000000, 150, 3);
+        let supply: u128 = 100_000_000_000;
+        let (mut position, mut reserve) = (0u128, 0u128);
+        for amount in [1_000_000_000u128, 777, 123_456_789, 50_000_000_000] {
+            let (quote_in, tokens_out) = curve
+                .swap_a_to_b_at_position(amount, position, reserve, supply - position)
+                .unwrap();
+            // the tokens out are what the quote buys on the curve, rounded down by at most a token
+            let cost = |tokens: u128| curve.buyout_cost(position as u64, tokens as u64).unwrap();
+            assert!(cost(tokens_out) <= quote_in + 1);
+            assert!(cost(tokens_out + 2) > quote_in);
+            position += tokens_out;
+            reserve += quote_in;
+        }
+
+        // selling everything back never pays out more than was put in, each buy keeps at most
+        // a couple of tokens worth (~55 lamports each) of rounding
+        let (tokens_in, quote_out) = curve.swap_b_to_a_at_position(position, position, reserve).unwrap();
+        assert_eq!(tokens_in, position);
+        assert!(quote_out <= reserve);
+        assert!(reserve - quote_out <= 4 * 2 * 55);
+
+        // a sell past the position only takes back what was bonded
+        assert_eq!(curve.swap_b_to_a_at_position(u64::MAX.into(), position, reserve).unwrap().0, position);
+
+        // a reserve below the curve at the stored position is rejected
+        assert_eq!(
+            curve.swap_b_to_a_at_position(1_000_000, position, reserve / 2),
+            Err(CurveError::InconsistentPosition)
+        );
+        assert_eq!(
+            curve.swap_a_to_b_at_position(1_000, position, 0, supply - position),
+            Err(CurveError::InconsistentPosition)
+        );
+        assert_eq!(curve.swap_a_to_b_at_position(1, 0, 0, supply), Err(CurveError::ZeroOutput));
+        // a remaining supply too large to price overflows instead of being treated as unlimited
+        assert_eq!(curve.swap_a_to_b_at_position(1_000, 0, 0, u128::MAX), Err(CurveError::Overflow));
+    }
+}
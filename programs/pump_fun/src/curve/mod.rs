@@ -1,3 +1,5 @@
+pub mod error;
 pub mod linear_price;
 
+pub use error::*;
 pub use linear_price::*;
\ No newline at end of file
@@ -5,6 +5,10 @@
 //! since we're only ever operating on wrapped u64 type numbers
 //! Also fixes some quirks from PreciseNumber around to_imprecise and removes pow/root
 //! since we don't need those (could add them back in if we did more testing around precision)
+//! Multiplication and division run on u128 whenever the operands and intermediates fit, which is
+//! most of the curve math, and only fall back to U256 otherwise
+//! Every operation that can lose digits takes an explicit `Rounding`, so callers state which side
+//! of the exact result they end up on
 
 use spl_math::uint::U256;
 
@@ -19,6 +23,47 @@ pub const ONE: u128 = 1_000000_000000_000000;
 /// Used for sqrt_u64 to correct precision calculation
 pub const SQRT_ONE: u128 = 1000_000000;
 
+/// Direction to round a result that has more digits than the 18 decimals
+/// (or, for `to_imprecise`, than a whole number)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero
+    Down,
+    /// Away from zero
+    Up,
+    /// To the nearest value, halves away from zero
+    HalfUp,
+}
+
+impl Rounding {
+    /// The direction to use for a term that is subtracted from a result rounded this way
+    pub fn opposite(self) -> Self {
+        match self {
+            Rounding::Down => Rounding::Up,
+            Rounding::Up => Rounding::Down,
+            Rounding::HalfUp => Rounding::HalfUp,
+        }
+    }
+
+    /// Added to the dividend so truncating division by `divisor` rounds this way
+    fn correction(self, divisor: InnerUint) -> InnerUint {
+        match self {
+            Rounding::Down => zero(),
+            Rounding::Up => divisor - InnerUint::one(),
+            Rounding::HalfUp => divisor / 2,
+        }
+    }
+
+    /// `correction` for the u128 fast paths
+    fn correction_u128(self, divisor: u128) -> u128 {
+        match self {
+            Rounding::Down => 0,
+            Rounding::Up => divisor - 1,
+            Rounding::HalfUp => divisor / 2,
+        }
+    }
+}
+
 /// Struct encapsulating a fixed-point number that allows for decimal calculations
 #[derive(Clone, Debug, PartialEq)]
 pub struct DFSPreciseNumber {
@@ -37,13 +82,6 @@ fn zero() -> InnerUint {
 }
 
 impl DFSPreciseNumber {
-    /// Correction to apply to avoid truncation errors on division.  Since
-    /// integer operations will always floor the result, we artifically bump it
-    /// up by one half to get the expect result.
-    fn rounding_correction() -> InnerUint {
-        InnerUint::from(ONE / 2)
-    }
-
     fn zero() -> Self {
         Self { value: zero() }
     }
@@ -54,11 +92,11 @@ impl DFSPreciseNumber {
         Some(Self { value })
     }
 
-    /// Convert a precise number back to u128
-    pub fn to_imprecise(&self) -> Option<u128> {
+    /// Convert a precise number back to u128, rounding the decimals away in the given direction
+    pub fn to_imprecise(&self, rounding: Rounding) -> Option<u128> {
         let corrected = self
             .value
-            .checked_add(Self::rounding_correction())?
+            .checked_add(rounding.correction(one()))?
             .checked_div(one());
 
         // don't panic if self > u128 max (this differs from spl_math::PreciseNumber)
@@ -94,14 +132,17 @@ impl DFSPreciseNumber {
         self.value >= rhs.value
     }
 
-    /// Floors a precise value to a precision of ONE
-    pub fn floor(&self) -> Option<Self> {
+    /// Floors a precise value to a precision of ONE. The curve rounds to whole numbers through
+    /// `to_imprecise` with an explicit `Rounding`, this and `ceiling` only back the tests
+    #[cfg(test)]
+    fn floor(&self) -> Option<Self> {
         let value = self.value.checked_div(one())?.checked_mul(one())?;
         Some(Self { value })
     }
 
     /// Ceiling a precise value to a precision of ONE
-    pub fn ceiling(&self) -> Option<Self> {
+    #[cfg(test)]
+    fn ceiling(&self) -> Option<Self> {
         let value = self
             .value
             .checked_add(one().checked_sub(InnerUint::from(1))?)?
@@ -110,22 +151,65 @@ impl DFSPreciseNumber {
         Some(Self { value })
     }
 
-    /// Performs a checked division on two precise numbers
-    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+    /// Both values as u128 if they fit, for the fast paths below. A `compute-bench` build never
+    /// takes them, so what they save can be measured on the program's own instructions
+    fn as_u128_pair(&self, rhs: &Self) -> Option<(u128, u128)> {
+        if cfg!(feature = "compute-bench") || self.value.bits() > 128 || rhs.value.bits() > 128 {
+            return None;
+        }
+        Some((self.value.as_u128(), rhs.value.as_u128()))
+    }
+
+    /// `(lhs * rhs + correction) / ONE` in u128, None if an intermediate doesn't fit.
+    /// Splitting `rhs` into its whole and fractional part keeps the result bit for bit the
+    /// same as the U256 version, and the fractional part is 0 whenever rhs is a whole number
+    /// (every `DFSPreciseNumber::new` operand in the curve)
+    fn mul_u128(lhs: u128, rhs: u128, rounding: Rounding) -> Option<u128> {
+        // split whichever operand has the smaller fractional part
+        let (lhs, rhs) = if lhs % ONE < rhs % ONE { (rhs, lhs) } else { (lhs, rhs) };
+        let whole = lhs.checked_mul(rhs / ONE)?;
+        let fraction = lhs.checked_mul(rhs % ONE)?.checked_add(rounding.correction_u128(ONE))? / ONE;
+        whole.checked_add(fraction)
+    }
+
+    /// `(lhs * ONE + correction) / rhs` in u128, None if an intermediate doesn't fit.
+    /// The whole multiples of `rhs` in `lhs` are divided out first, so only the remainder
+    /// (always below rhs) is scaled by ONE, the result is the same as the U256 version
+    fn div_u128(lhs: u128, rhs: u128, rounding: Rounding) -> Option<u128> {
+        let whole = (lhs / rhs).checked_mul(ONE)?;
+        let fraction = (lhs % rhs).checked_mul(ONE)?.checked_add(rounding.correction_u128(rhs))? / rhs;
+        whole.checked_add(fraction)
+    }
+
+    /// Performs a checked division on two precise numbers, rounding the last decimal in the given direction
+    pub fn checked_div(&self, rhs: &Self, rounding: Rounding) -> Option<Self> {
+        if *rhs == Self::zero() {
+            return None;
+        }
+        // u128 arithmetic is several times cheaper in compute than U256
+        match self.as_u128_pair(rhs).and_then(|(lhs, rhs)| Self::div_u128(lhs, rhs, rounding)) {
+            Some(value) => Some(Self { value: InnerUint::from(value) }),
+            None => self.checked_div_u256(rhs, rounding),
+        }
+    }
+
+    /// Division on the full U256 values, for operands the u128 path can't take
+    fn checked_div_u256(&self, rhs: &Self, rounding: Rounding) -> Option<Self> {
         if *rhs == Self::zero() {
             return None;
         }
         match self.value.checked_mul(one()) {
             Some(v) => {
                 let value = v
-                    .checked_add(Self::rounding_correction())?
+                    .checked_add(rounding.correction(rhs.value))?
                     .checked_div(rhs.value)?;
                 Some(Self { value })
             }
             None => {
+                // too large to scale first, so the quotient is only rounded to a whole number
                 let value = self
                     .value
-                    .checked_add(Self::rounding_correction())?
+                    .checked_add(rounding.correction(rhs.value))?
                     .checked_div(rhs.value)?
                     .checked_mul(one())?;
                 Some(Self { value })
@@ -133,21 +217,40 @@ impl DFSPreciseNumber {
         }
     }
 
-    /// Performs a multiplication on two precise numbers
-    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+    /// Performs a multiplication on two precise numbers, rounding the last decimal in the given direction
+    pub fn checked_mul(&self, rhs: &Self, rounding: Rounding) -> Option<Self> {
+        match self.as_u128_pair(rhs).and_then(|(lhs, rhs)| Self::mul_u128(lhs, rhs, rounding)) {
+            Some(value) => Some(Self { value: InnerUint::from(value) }),
+            None => self.checked_mul_u256(rhs, rounding),
+        }
+    }
+
+    /// Multiplication on the full U256 values, for operands the u128 path can't take
+    fn checked_mul_u256(&self, rhs: &Self, rounding: Rounding) -> Option<Self> {
         match self.value.checked_mul(rhs.value) {
             Some(v) => {
                 let value = v
-                    .checked_add(Self::rounding_correction())?
+                    .checked_add(rounding.correction(one()))?
                     .checked_div(one())?;
                 Some(Self { value })
             }
             None => {
-                let value = if self.value >= rhs.value {
-                    self.value.checked_div(one())?.checked_mul(rhs.value)?
+                // too large for the product, the larger operand is cut to a whole number first.
+                // Only rounding up changes that cut, truncating stays within a half for HalfUp
+                // at this magnitude
+                let (larger, smaller) = if self.value >= rhs.value {
+                    (self.value, rhs.value)
                 } else {
-                    rhs.value.checked_div(one())?.checked_mul(self.value)?
+                    (rhs.value, self.value)
+                };
+                let correction = match rounding {
+                    Rounding::Up => rounding.correction(one()),
+                    Rounding::Down | Rounding::HalfUp => zero(),
                 };
+                let value = larger
+                    .checked_add(correction)?
+                    .checked_div(one())?
+                    .checked_mul(smaller)?;
                 Some(Self { value })
             }
         }
@@ -177,7 +280,8 @@ impl DFSPreciseNumber {
     }
 
     pub fn to_spl_precise_number(&self) -> Option<spl_math::precise_number::PreciseNumber> {
-        let value_u128 = self.to_imprecise()?;
+        // the decimals are added back below, so only the whole part is taken here
+        let value_u128 = self.to_imprecise(Rounding::Down)?;
         let spl_number = spl_math::precise_number::PreciseNumber::new(value_u128)?;
 
         // add on the decimals manually
@@ -255,13 +359,14 @@ impl DFSPreciseNumber {
 
             // so real_sqrt = sqrt_padded * sqrt(ONE) / sqrt(2^pad_bits)
             // (do this after converting to PreciseNumber so we don't lose precision)
-            let unrounded_numerator = sqrt_padded.checked_mul(&(Self::new(SQRT_ONE)?))?;
-            let unrounded_sqrt = unrounded_numerator.checked_div(&correction_factor)?;
+            // (multiplying by whole numbers is exact, so the rounding passed there doesn't matter)
+            let unrounded_numerator = sqrt_padded.checked_mul(&(Self::new(SQRT_ONE)?), Rounding::Down)?;
+            let unrounded_sqrt = unrounded_numerator.checked_div(&correction_factor, Rounding::Down)?;
 
             // finally, round up if it wasn't a perfect division and we should round up
             real_sqrt = match should_round_up
                 && unrounded_sqrt
-                    .checked_mul(&correction_factor)?
+                    .checked_mul(&correction_factor, Rounding::Down)?
                     .less_than(&unrounded_numerator)
             {
                 true => unrounded_sqrt.checked_add(
@@ -312,31 +417,81 @@ impl DFSPreciseNumber {
             // so real_sqrt = sqrt_padded * sqrt(ONE) * sqrt(2^pad_bits)
             // (do this after converting to PreciseNumber so we don't lose precision)
             real_sqrt = sqrt_padded
-                .checked_mul(&(Self::new(SQRT_ONE)?))?
-                .checked_mul(&correction_factor)
+                .checked_mul(&(Self::new(SQRT_ONE)?), Rounding::Down)?
+                .checked_mul(&correction_factor, Rounding::Down)
         }
 
         real_sqrt
     }
+
+    /// Exact integer square root of a U256, rounded down. Newton's method started from the
+    /// power of two just above the root only ever decreases, and since the start is within 2x of
+    /// the root it converges in at most 8 steps (each one U256 division) for any 256 bit input,
+    /// 7 for the radicands of u128 values (see `test_isqrt_steps`)
+    fn isqrt(value: InnerUint) -> InnerUint {
+        Self::isqrt_with_steps(value).0
+    }
+
+    /// `isqrt` along with the number of Newton steps it took
+    fn isqrt_with_steps(value: InnerUint) -> (InnerUint, u32) {
+        if value.is_zero() {
+            return (zero(), 0);
+        }
+        let mut x = InnerUint::one() << value.bits().div_ceil(2);
+        let mut steps = 0;
+        loop {
+            steps += 1;
+            let y = (x + value / x) >> 1;
+            if y >= x {
+                return (x, steps);
+            }
+            x = y;
+        }
+    }
+
+    /// Square root exact to the last of the 18 decimals, rounded in the given direction.
+    /// Unlike `sqrt_u64` no precision is dropped, so callers don't need to round against the
+    /// user to cover the error. Values too large to scale by ONE (above ~2^196) fall back to
+    /// rooting the whole part, still rounded in the requested direction
+    pub fn sqrt(&self, rounding: Rounding) -> Option<Self> {
+        // sqrt(value / ONE) * ONE == sqrt(value * ONE)
+        let (radicand, scale) = match self.value.checked_mul(one()) {
+            Some(radicand) => (radicand, InnerUint::one()),
+            None => (self.value, InnerUint::from(SQRT_ONE)),
+        };
+        let root = Self::isqrt(radicand);
+        // the remainder over root^2 is at most 2 * root, (root + 0.5)^2 == root^2 + root + 0.25
+        let remainder = radicand - root * root;
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => !remainder.is_zero(),
+            Rounding::HalfUp => remainder > root,
+        };
+        let root = if round_up { root + InnerUint::one() } else { root };
+        let value = root.checked_mul(scale)?;
+        Some(Self { value })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
     use solana_program::msg;
 
     #[test]
     fn test_to_imprecise() {
         let number = DFSPreciseNumber::new(0).unwrap();
-        assert_eq!(number.floor().unwrap().to_imprecise().unwrap(), 0);
+        assert_eq!(number.floor().unwrap().to_imprecise(Rounding::HalfUp).unwrap(), 0);
 
         let number = DFSPreciseNumber::new(u128::MAX).unwrap();
-        assert_eq!(number.to_imprecise().unwrap(), u128::MAX);
+        assert_eq!(number.to_imprecise(Rounding::HalfUp).unwrap(), u128::MAX);
 
         // should just return None instead of panic if overflow
         let number = DFSPreciseNumber::new(u128::MAX).unwrap();
         let number = number.checked_add(&number).unwrap();
-        assert!(number.to_imprecise().is_none());
+        assert!(number.to_imprecise(Rounding::HalfUp).is_none());
     }
 
     #[test]
@@ -344,13 +499,13 @@ mod tests {
         // number below 1 (with uneven number of bits) 1.23456789e-9
         let number = DFSPreciseNumber::new(123456789)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(17)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(17)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert_eq!(number.value.bits(), 31);
         // sqrt is 3.51364182864446216-5
         let expected_sqrt = DFSPreciseNumber::new(351364182864446216)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(22)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(22)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number
@@ -366,13 +521,13 @@ mod tests {
         // number below 1 (with even number of bits) 1e-8
         let number = DFSPreciseNumber::new(1)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(8)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(8)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert_eq!(number.value.bits(), 34);
         // sqrt is 1-e4
         let expected_sqrt = DFSPreciseNumber::new(1)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(4)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(4)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number
@@ -388,13 +543,13 @@ mod tests {
         // exactly max_bits 18446744073709551615e-18 (this is 64 bits of 1, then divided by ONE)
         let number = DFSPreciseNumber::new(18446744073709551615)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert_eq!(number.value.bits(), 64);
         // sqrt is 4.29496729599999999988
         let expected_sqrt = DFSPreciseNumber::new(4294967295999999999)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number
@@ -428,7 +583,7 @@ mod tests {
         // sqrt is 1111111106.111111099355555502655555
         let decimals = DFSPreciseNumber::new(111111099355555502655555)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(24)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(24)).unwrap()), Rounding::HalfUp)
             .unwrap();
         let expected_sqrt = DFSPreciseNumber::new(1111111106)
             .unwrap()
@@ -448,7 +603,7 @@ mod tests {
         // super large number, odd bits (pretty close to max value of u128) 1.23456789e38
         let number = DFSPreciseNumber::new(123456789)
             .unwrap()
-            .checked_mul(&(DFSPreciseNumber::new(10u128.pow(30)).unwrap()))
+            .checked_mul(&(DFSPreciseNumber::new(10u128.pow(30)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert_eq!(number.value.bits(), 187);
         // sqrt is 11111111060555555440.5
@@ -472,12 +627,12 @@ mod tests {
         // small perfect square (4e-18), should_round_up=false
         let number = DFSPreciseNumber::new(4)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()), Rounding::HalfUp)
             .unwrap();
         // 2e-9, shouldn't do any rounding
         let expected_sqrt = DFSPreciseNumber::new(2)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(9)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(9)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number.sqrt_u64(false).unwrap().eq(&expected_sqrt),
@@ -489,12 +644,12 @@ mod tests {
         // small perfect square (4e-18), should_round_up=true
         let number = DFSPreciseNumber::new(4)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()), Rounding::HalfUp)
             .unwrap();
         // 2e-9
         let expected_sqrt = DFSPreciseNumber::new(2)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(9)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(9)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number.sqrt_u64(true).unwrap().eq(&expected_sqrt),
@@ -506,12 +661,12 @@ mod tests {
         // small imperfect square (3e-18), should_round_up=false
         let number = DFSPreciseNumber::new(3)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()), Rounding::HalfUp)
             .unwrap();
         // 1.7320508075688e-9 (only room for first 10 digits), should round down to 1.732050807e-9
         let expected_sqrt = DFSPreciseNumber::new(1732050807)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number.sqrt_u64(false).unwrap().eq(&expected_sqrt),
@@ -523,12 +678,12 @@ mod tests {
         // small imperfect square (3e-18), should_round_up=true
         let number = DFSPreciseNumber::new(3)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()), Rounding::HalfUp)
             .unwrap();
         // 1.7320508075688e-9 (only room for first 10 digits), should round down to 1.732050808e-9
         let expected_sqrt = DFSPreciseNumber::new(1732050808)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(18)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number.sqrt_u64(true).unwrap().eq(&expected_sqrt),
@@ -562,7 +717,7 @@ mod tests {
         // 17.32050807568
         let expected_sqrt = DFSPreciseNumber::new(1732050807568)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(11)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(11)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number
@@ -592,7 +747,7 @@ mod tests {
         // 17.32050807568
         let expected_sqrt = DFSPreciseNumber::new(1732050807568)
             .unwrap()
-            .checked_div(&(DFSPreciseNumber::new(10u128.pow(11)).unwrap()))
+            .checked_div(&(DFSPreciseNumber::new(10u128.pow(11)).unwrap()), Rounding::HalfUp)
             .unwrap();
         assert!(
             number
@@ -639,4 +794,125 @@ mod tests {
         assert_eq!(whole_number.value, ceiling.value);
         assert_eq!(whole_number.value, ceiling_again.value);
     }
+
+    /// Raw values, whole numbers like the curve's `DFSPreciseNumber::new` operands, and values
+    /// too large for the u128 path
+    fn precise_number() -> impl Strategy<Value = DFSPreciseNumber> {
+        prop_oneof![
+            any::<u128>().prop_map(InnerUint::from),
+            (0..u128::from(u64::MAX)).prop_map(|value| InnerUint::from(value * ONE)),
+            any::<[u64; 4]>().prop_map(U256),
+        ]
+        .prop_map(|value| DFSPreciseNumber { value })
+    }
+
+    proptest! {
+        #[test]
+        fn test_u128_path_matches_u256(lhs in precise_number(), rhs in precise_number()) {
+            for rounding in [Rounding::Down, Rounding::Up, Rounding::HalfUp] {
+                prop_assert_eq!(lhs.checked_mul(&rhs, rounding), lhs.checked_mul_u256(&rhs, rounding));
+                prop_assert_eq!(lhs.checked_div(&rhs, rounding), lhs.checked_div_u256(&rhs, rounding));
+            }
+        }
+    }
+
+    fn to_big_uint(value: InnerUint) -> BigUint {
+        let mut bytes = [0u8; 32];
+        value.to_little_endian(&mut bytes);
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    #[test]
+    fn test_sqrt() {
+        let four = DFSPreciseNumber::new(4).unwrap();
+        for rounding in [Rounding::Down, Rounding::Up, Rounding::HalfUp] {
+            assert_eq!(four.sqrt(rounding).unwrap(), DFSPreciseNumber::new(2).unwrap());
+            assert_eq!(DFSPreciseNumber::zero().sqrt(rounding).unwrap(), DFSPreciseNumber::zero());
+        }
+        // sqrt(2) = 1.414213562373095048(8...)
+        let two = DFSPreciseNumber::new(2).unwrap();
+        assert_eq!(two.sqrt(Rounding::Down).unwrap().value, InnerUint::from(1_414213562_373095048u128));
+        assert_eq!(two.sqrt(Rounding::Up).unwrap().value, InnerUint::from(1_414213562_373095049u128));
+        assert_eq!(two.sqrt(Rounding::HalfUp).unwrap().value, InnerUint::from(1_414213562_373095049u128));
+        // sqrt(3) = 1.732050807568877293(5...)
+        let three = DFSPreciseNumber::new(3).unwrap();
+        assert_eq!(three.sqrt(Rounding::HalfUp).unwrap().value, InnerUint::from(1_732050807_568877294u128));
+        // too large to scale by ONE, only the whole part is rooted
+        let max = DFSPreciseNumber { value: InnerUint::MAX };
+        assert!(max.sqrt(Rounding::Down).unwrap().value <= max.sqrt(Rounding::Up).unwrap().value);
+    }
+
+    #[test]
+    fn test_rounding() {
+        // 2 / 3 = 0.666666666666666666(6...)
+        let two = DFSPreciseNumber::new(2).unwrap();
+        let three = DFSPreciseNumber::new(3).unwrap();
+        assert_eq!(two.checked_div(&three, Rounding::Down).unwrap().value, InnerUint::from(666666666_666666666u128));
+        assert_eq!(two.checked_div(&three, Rounding::Up).unwrap().value, InnerUint::from(666666666_666666667u128));
+        assert_eq!(two.checked_div(&three, Rounding::HalfUp).unwrap().value, InnerUint::from(666666666_666666667u128));
+        // 0.5 * 0.000000000000000001
+        let half = DFSPreciseNumber { value: InnerUint::from(ONE / 2) };
+        let ulp = DFSPreciseNumber { value: InnerUint::one() };
+        assert_eq!(half.checked_mul(&ulp, Rounding::Down).unwrap().value, zero());
+        assert_eq!(half.checked_mul(&ulp, Rounding::Up).unwrap().value, InnerUint::one());
+        assert_eq!(half.checked_mul(&ulp, Rounding::HalfUp).unwrap().value, InnerUint::one());
+        // 2.5 as a whole number
+        let two_and_a_half = DFSPreciseNumber { value: InnerUint::from(5 * ONE / 2) };
+        assert_eq!(two_and_a_half.to_imprecise(Rounding::Down).unwrap(), 2);
+        assert_eq!(two_and_a_half.to_imprecise(Rounding::Up).unwrap(), 3);
+        assert_eq!(two_and_a_half.to_imprecise(Rounding::HalfUp).unwrap(), 3);
+        assert_eq!(Rounding::Down.opposite(), Rounding::Up);
+        assert_eq!(Rounding::HalfUp.opposite(), Rounding::HalfUp);
+    }
+
+    #[test]
+    fn test_isqrt_steps() {
+        let steps = |value: InnerUint| DFSPreciseNumber::isqrt_with_steps(value).1;
+        assert_eq!(steps(zero()), 0);
+        // powers of four start the furthest above the root, just below them the closest
+        for bits in 0..256 {
+            let power = InnerUint::one() << bits;
+            assert!(steps(power) <= 8);
+            assert!(steps(power - InnerUint::one()) <= 8);
+            assert!(steps(power + InnerUint::one()) <= 8);
+        }
+        assert!(steps(InnerUint::MAX) <= 8);
+        // the largest radicand `sqrt` scales a u128 value to
+        assert!(steps(InnerUint::from(u128::MAX) * one()) <= 7);
+    }
+
+    proptest! {
+        #[test]
+        fn test_isqrt_steps_bounded(words in any::<[u64; 4]>(), bits in 1usize..=256) {
+            let value = U256(words) >> (256 - bits);
+            prop_assert!(DFSPreciseNumber::isqrt_with_steps(value).1 <= 8);
+        }
+
+        #[test]
+        fn test_sqrt_steps_bounded(value in any::<u128>()) {
+            prop_assert!(DFSPreciseNumber::isqrt_with_steps(InnerUint::from(value) * one()).1 <= 7);
+        }
+
+        #[test]
+        fn test_isqrt_matches_big_uint(words in any::<[u64; 4]>()) {
+            let value = U256(words);
+            prop_assert_eq!(
+                to_big_uint(DFSPreciseNumber::isqrt(value)),
+                to_big_uint(value).sqrt()
+            );
+        }
+
+        #[test]
+        fn test_sqrt_matches_big_uint(value in any::<u128>()) {
+            let number = DFSPreciseNumber { value: InnerUint::from(value) };
+            let radicand = BigUint::from(value) * BigUint::from(ONE);
+            let floor = radicand.sqrt();
+            let remainder = &radicand - &floor * &floor;
+            let ceil = if remainder > BigUint::from(0u8) { &floor + 1u8 } else { floor.clone() };
+            let nearest = if remainder > floor { &floor + 1u8 } else { floor.clone() };
+            prop_assert_eq!(to_big_uint(number.sqrt(Rounding::Down).unwrap().value), floor);
+            prop_assert_eq!(to_big_uint(number.sqrt(Rounding::Up).unwrap().value), ceil);
+            prop_assert_eq!(to_big_uint(number.sqrt(Rounding::HalfUp).unwrap().value), nearest);
+        }
+    }
 }
@@ -98,6 +98,128 @@ pub enum SwapError {
     /// The operation cannot be performed on the given curve
     #[msg("The operation cannot be performed on the given curve")]
     UnsupportedCurveOperation,
+    /// The signer is not the owner of the app state
+    #[msg("Signer is not the app owner")]
+    InvalidAppOwner,
+    /// The signer is not the owner proposed by the current owner
+    #[msg("Signer is not the pending app owner")]
+    InvalidPendingOwner,
+
+    // 30.
+    /// The pair does not hold enough sol to graduate
+    #[msg("The pair has not reached the graduation threshold")]
+    GraduationThresholdNotReached,
+    /// The governance signer set, threshold or delay is invalid
+    #[msg("Invalid governance configuration")]
+    InvalidGovernanceConfig,
+    /// The signer is not part of the governance signer set
+    #[msg("Signer is not a governance signer")]
+    NotGovernanceSigner,
+    /// The signer already approved the proposal
+    #[msg("Proposal already approved by signer")]
+    ProposalAlreadyApproved,
+    /// The proposal does not have enough approvals
+    #[msg("Proposal has not reached the approval threshold")]
+    ProposalNotApproved,
+
+    // 35.
+    /// The proposal timelock has not elapsed
+    #[msg("Proposal timelock has not elapsed")]
+    TimelockNotElapsed,
+    /// The proposal was already executed or cancelled
+    #[msg("Proposal was already executed or cancelled")]
+    ProposalClosed,
+    /// The proposal does not match the requested admin action
+    #[msg("Proposal action does not match the instruction")]
+    ProposalActionMismatch,
+    /// Governance is enabled and the instruction was sent without a proposal
+    #[msg("Admin actions require an approved governance proposal")]
+    ProposalRequired,
+    /// The governance signer set changed after the proposal was created
+    #[msg("Proposal was created for a previous governance configuration")]
+    StaleProposal,
+
+    // 40.
+    /// Trading in this direction is paused globally or for the pair
+    #[msg("Trading is paused")]
+    TradingPaused,
+    /// Launching new tokens and pairs is paused
+    #[msg("Token creation is paused")]
+    CreatePaused,
+    /// The account belongs to a different launchpad namespace
+    #[msg("Account belongs to a different app state namespace")]
+    NamespaceMismatch,
+    /// The custom curve is outside of the owner's bounds
+    #[msg("Curve parameters are outside of the allowed bounds")]
+    CurveOutOfBounds,
+    /// The curve preset is disabled
+    #[msg("Curve preset is disabled")]
+    CurvePresetDisabled,
+
+    // 45.
+    /// Only the token creator can initialize the pair for its mint
+    #[msg("Signer is not the token creator")]
+    NotTokenCreator,
+    /// The quote mint is not on the app state allowlist
+    #[msg("Quote mint is not allowed")]
+    QuoteMintNotAllowed,
+    /// The quote allowlist has no free slot left
+    #[msg("Quote mint allowlist is full")]
+    QuoteAllowlistFull,
+    /// Native sol swaps only work on pairs quoted in wsol
+    #[msg("Pair is not quoted in wsol")]
+    NativeQuoteRequired,
+    /// The mint's transfer hook program is not on the app state allowlist
+    #[msg("Transfer hook program is not allowed")]
+    TransferHookNotAllowed,
+
+    // 50.
+    /// The transfer hook allowlist has no free slot left
+    #[msg("Transfer hook allowlist is full")]
+    HookAllowlistFull,
+    /// A permanent delegate could move tokens out of any holder's account
+    #[msg("Mint has a permanent delegate")]
+    PermanentDelegateNotAllowed,
+    /// New token accounts of the mint would start frozen
+    #[msg("Mint accounts are frozen by default")]
+    DefaultAccountStateFrozen,
+    /// Tokens of the mint cannot be transferred, so they cannot be traded
+    #[msg("Mint is non-transferable")]
+    NonTransferableMint,
+    /// The supply is above the app state cap
+    #[msg("Supply exceeds the launch cap")]
+    SupplyCapExceeded,
+
+    // 55.
+    /// The mint decimals are outside the app state policy, or the policy range is empty
+    #[msg("Mint decimals are not allowed")]
+    InvalidDecimals,
+    /// The twap window starts before the oldest observation kept by the pair
+    #[msg("Not enough price history for the window")]
+    ObservationTooOld,
+    /// A twap needs a window of at least one second
+    #[msg("Invalid twap window")]
+    InvalidTwapWindow,
+    /// The reserve the trade pays out of is empty
+    #[msg("Not enough liquidity on the curve for the trade")]
+    InsufficientLiquidity,
+    /// The pair's quote reserve doesn't cover the curve at its tokens sold
+    #[msg("Pair reserves are out of sync with its curve position")]
+    InconsistentReserves,
+
+    // 60.
+    /// Only the program upgrade authority or the root namespace owner can create a namespace
+    #[msg("Signer cannot create an app state namespace")]
+    NamespaceNotAuthorized,
+    /// The pair's liquidity has moved to the pool, the curve no longer trades
+    #[msg("Pair has graduated")]
+    PairGraduated,
+    /// Whoever holds the transfer fee config authority could raise the fee on holders
+    #[msg("Mint transfer fee authority is not the program")]
+    TransferFeeAuthorityNotAllowed,
+    /// Whoever holds the transfer hook authority could point the mint at another hook program
+    #[msg("Mint transfer hook authority is not the program")]
+    TransferHookAuthorityNotAllowed,
 }
 impl From<SwapError> for ProgramError {
     fn from(e: SwapError) -> Self {
@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    curve::LinearPriceCurve,
+    CurveBounds,
+    CurvePresetParams,
+    GovernanceParams,
+    PauseMode,
+    SupplyPolicy,
+};
+
+#[event]
+pub struct PauseChanged {
+    /// mint of the paused pair, None for the global pause
+    pub mint: Option<Pubkey>,
+    pub previous_mode: PauseMode,
+    pub mode: PauseMode,
+}
+
+#[event]
+pub struct TokenCreated {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub app_stats: Pubkey,
+    pub decimals: u8,
+    /// minted supply, all of it held by the pair vault
+    pub supply: u64,
+    pub curve_supply: u64,
+    pub reserve_supply: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PairInitialized {
+    pub pair: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub creator: Pubkey,
+    /// curve as stored, already rescaled to the quote mint decimals
+    pub curve: LinearPriceCurve,
+    pub graduation_threshold: u64,
+    pub real_token_reserves: u64,
+    pub graduation_reserve: u64,
+    pub slot: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+#[event]
+pub struct Trade {
+    pub side: TradeSide,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub trader: Pubkey,
+    /// debited from the trader, quote on a buy and tokens on a sell, fees included
+    pub amount_in: u64,
+    /// credited to the trader after every fee
+    pub amount_out: u64,
+    /// treasury wallet the trade fee was sent to
+    pub treasury: Pubkey,
+    /// trade fee in quote units
+    pub treasury_fee: u64,
+    /// withheld by the quote mint's transfer fee on the curve leg
+    pub quote_transfer_fee: u64,
+    /// withheld by the token mint's transfer fee on the curve leg
+    pub token_transfer_fee: u64,
+    pub real_quote_reserves: u64,
+    pub real_token_reserves: u64,
+    /// quote base units per whole token after the trade
+    pub spot_price: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct Graduated {
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub pool_state: Pubkey,
+    /// tokens and quote deposited into the pool
+    pub token_amount: u64,
+    pub quote_amount: u64,
+    /// tokens burned from the vault before the deposit
+    pub burned: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub app_stats: Pubkey,
+    pub authority: Pubkey,
+    /// config values in effect after the update
+    pub fee_lamports: u64,
+    pub fee_account: Pubkey,
+    pub fee_basis_points: u16,
+    pub graduation_threshold: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OwnerProposed {
+    pub app_stats: Pubkey,
+    pub authority: Pubkey,
+    /// owner that can accept, the default pubkey when a pending transfer was cancelled
+    pub pending_owner: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OwnerAccepted {
+    pub app_stats: Pubkey,
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct GovernanceUpdated {
+    pub app_stats: Pubkey,
+    pub governance: Pubkey,
+    /// signer set, threshold and delay in effect after the update
+    pub params: GovernanceParams,
+    /// proposals created under an earlier version can no longer be executed
+    pub config_version: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CurvePresetSet {
+    pub app_stats: Pubkey,
+    pub authority: Pubkey,
+    pub curve_preset: Pubkey,
+    pub preset_id: u64,
+    pub params: CurvePresetParams,
+    pub slot: u64,
+}
+
+#[event]
+pub struct CurveBoundsSet {
+    pub app_stats: Pubkey,
+    pub authority: Pubkey,
+    pub bounds: CurveBounds,
+    pub slot: u64,
+}
+
+#[event]
+pub struct QuoteMintSet {
+    pub app_stats: Pubkey,
+    pub authority: Pubkey,
+    pub quote_mint: Pubkey,
+    /// whether new pairs can launch against the mint after the change
+    pub allowed: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct HookProgramSet {
+    pub app_stats: Pubkey,
+    pub authority: Pubkey,
+    pub hook_program: Pubkey,
+    /// whether mints using the hook can launch and trade after the change
+    pub allowed: bool,
+    pub slot: u64,
+}
+
+#[event]
+pub struct SupplyPolicySet {
+    pub app_stats: Pubkey,
+    pub authority: Pubkey,
+    /// policy new launches are checked against after the change
+    pub policy: SupplyPolicy,
+    pub slot: u64,
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::SwapError, AppStats, OwnerAccepted, APP_STATS_SEED };
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
+    pub new_owner: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        constraint = app_stats.pending_owner == new_owner.key() @ SwapError::InvalidPendingOwner,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+}
+
+/*
+    @dev second step of ownership transfer, signed by the proposed owner.
+*/
+pub fn accept_owner_handler(ctx: Context<AcceptOwner>) -> Result<()> {
+    let app_stats: &mut Box<Account<AppStats>> = &mut ctx.accounts.app_stats;
+    let previous_owner: Pubkey = app_stats.owner;
+    app_stats.owner = ctx.accounts.new_owner.key();
+    app_stats.pending_owner = Pubkey::default();
+    emit_cpi!(OwnerAccepted {
+        app_stats: app_stats.key(),
+        previous_owner,
+        owner: app_stats.owner,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::SwapError, Governance, Proposal, GOVERNANCE_SEED };
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+
+    // governance account
+    #[account(
+        seeds = [GOVERNANCE_SEED, governance.app_stats.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+
+    // proposal account
+    #[account(mut, has_one = governance)]
+    pub proposal: Box<Account<'info, Proposal>>,
+}
+
+/*
+    @dev approve a pending proposal by a governance signer, the timelock starts once the
+    approval threshold is reached
+*/
+pub fn approve_proposal_handler(ctx: Context<ApproveProposal>) -> Result<()> {
+    let signer_index = ctx.accounts.governance
+        .signer_index(&ctx.accounts.signer.key())
+        .ok_or(SwapError::NotGovernanceSigner)?;
+    ctx.accounts.proposal.approve(&ctx.accounts.governance, signer_index)
+}
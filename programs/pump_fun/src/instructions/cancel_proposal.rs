@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::SwapError, Governance, Proposal, GOVERNANCE_SEED };
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    pub signer: Signer<'info>,
+
+    // governance account
+    #[account(
+        seeds = [GOVERNANCE_SEED, governance.app_stats.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+
+    // proposal account
+    #[account(mut, has_one = governance)]
+    pub proposal: Box<Account<'info, Proposal>>,
+}
+
+/*
+    @dev cancel a pending proposal, any governance signer can veto it before execution
+*/
+pub fn cancel_proposal_handler(ctx: Context<CancelProposal>) -> Result<()> {
+    require!(
+        ctx.accounts.governance.signer_index(&ctx.accounts.signer.key()).is_some(),
+        SwapError::NotGovernanceSigner
+    );
+    let proposal: &mut Box<Account<Proposal>> = &mut ctx.accounts.proposal;
+    require!(!proposal.executed && !proposal.cancelled, SwapError::ProposalClosed);
+    proposal.cancelled = true;
+    Ok(())
+}
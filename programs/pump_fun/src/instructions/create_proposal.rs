@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use std::mem::size_of;
+
+use crate::{ error::SwapError, AdminAction, Governance, Proposal, GOVERNANCE_SEED, PROPOSAL_SEED };
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    // governance account
+    #[account(
+        mut,
+        seeds = [GOVERNANCE_SEED, governance.app_stats.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+
+    // proposal account
+    #[account(
+        init,
+        payer = proposer,
+        space = size_of::<Proposal>() + 8,
+        seeds = [
+            PROPOSAL_SEED,
+            governance.key().as_ref(),
+            governance.proposal_count.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+/*
+    @dev queue an admin action by a governance signer, the proposer's approval is counted.
+    @param action: admin action to execute once approved and the timelock has passed
+*/
+pub fn create_proposal_handler(ctx: Context<CreateProposal>, action: AdminAction) -> Result<()> {
+    let governance: &mut Box<Account<Governance>> = &mut ctx.accounts.governance;
+    let signer_index = governance
+        .signer_index(&ctx.accounts.proposer.key())
+        .ok_or(SwapError::NotGovernanceSigner)?;
+    let proposal: &mut Box<Account<Proposal>> = &mut ctx.accounts.proposal;
+    proposal.governance = governance.key();
+    proposal.id = governance.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.action = action;
+    proposal.config_version = governance.config_version;
+    proposal.approvals = 0;
+    proposal.approval_count = 0;
+    proposal.approved_at = 0;
+    proposal.executed = false;
+    proposal.cancelled = false;
+    proposal.bump = ctx.bumps.proposal;
+    proposal.approve(governance, signer_index)?;
+    governance.proposal_count = governance.proposal_count
+        .checked_add(1)
+        .ok_or(SwapError::CalculationFailure)?;
+    Ok(())
+}
@@ -1,16 +1,24 @@
 use anchor_lang::{ prelude::*, solana_program::{ program::invoke, system_instruction } };
-use anchor_spl::{ token_2022::{ self, MintTo }, token_interface };
+use anchor_spl::{
+    token_2022::{ self, spl_token_2022::instruction::AuthorityType, MintTo, SetAuthority },
+    token_interface,
+};
 
 use std::mem::size_of;
 use crate::{
+    error::SwapError,
+    utils::check_mint_safety,
     AppStats,
+    SupplyPolicy,
     TokenCreate,
+    TokenCreated,
     APP_STATS_SEED,
     AUTHORITY_SEED,
     TOKEN_ACCOUNT_SEED,
     TOKEN_CREATE_SEED,
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreateToken<'info> {
     #[account(mut)]
@@ -60,7 +68,11 @@ pub struct CreateToken<'info> {
     pub fee_account: AccountInfo<'info>,
 
     // app state account
-    #[account(seeds = [APP_STATS_SEED], bump)]
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
     pub app_stats: Box<Account<'info, AppStats>>,
     pub token_program: Interface<'info, token_interface::TokenInterface>,
     pub system_program: Program<'info, System>,
@@ -85,11 +97,19 @@ impl<'info> CreateToken<'info> {
             authority: self.authority.to_account_info(),
         })
     }
+
+    fn set_authority_ctx(&self) -> CpiContext<'info, 'info, 'info, 'info, SetAuthority<'info>> {
+        CpiContext::new(self.token_program.to_account_info(), SetAuthority {
+            current_authority: self.authority.to_account_info(),
+            account_or_mint: self.mint.to_account_info(),
+        })
+    }
 }
 
 
 /*
-    create token function
+    create token function, the whole supply is minted to the pair vault and the mint authority
+    is revoked so the supply stays fixed
     @param 
     supply: token supply amount
     bump: authority pda bump
@@ -101,6 +121,12 @@ pub fn create_token_handler(
     supply: u64,
     bump: u8
 ) -> Result<()> {
+    require!(ctx.accounts.app_stats.pause_mode.can_create(), SwapError::CreatePaused);
+    check_mint_safety(&ctx.accounts.mint, &ctx.accounts.authority.key())?;
+    // anything minted before the pda took over would sit outside the curve
+    require!(ctx.accounts.mint.supply == 0, SwapError::InvalidSupply);
+    let policy: SupplyPolicy = ctx.accounts.app_stats.supply_policy;
+    let (curve_supply, reserve_supply) = policy.split_supply(supply, ctx.accounts.mint.decimals)?;
     ctx.accounts.transfer_fee(ctx.accounts.app_stats.fee_lamports)?;
     let seeds: &[&[u8]; 3] = &[
         AUTHORITY_SEED,
@@ -109,9 +135,35 @@ pub fn create_token_handler(
     ];
     let signer_seeds: &[&[&[u8]]; 1] = &[&seeds[..]];
     token_2022::mint_to(ctx.accounts.mint_ctx().with_signer(signer_seeds), supply)?;
+    token_2022::set_authority(
+        ctx.accounts.set_authority_ctx().with_signer(signer_seeds),
+        AuthorityType::MintTokens,
+        None
+    )?;
+    ctx.accounts.mint.reload()?;
     let token_create: &mut Box<Account<TokenCreate>> = &mut ctx.accounts.token_create;
     token_create.creator = ctx.accounts.creator.key();
+    token_create.mint = ctx.accounts.mint.key();
     token_create.supply = ctx.accounts.mint.supply;
     token_create.bump = bump;
+    token_create.cap = policy.max_supply;
+    token_create.app_stats = ctx.accounts.app_stats.key();
+    token_create.curve_supply = curve_supply;
+    token_create.reserve_supply = reserve_supply;
+    token_create.burned = 0;
+    let fee_lamports: u64 = ctx.accounts.app_stats.fee_lamports;
+    let app_stats: &mut Box<Account<AppStats>> = &mut ctx.accounts.app_stats;
+    app_stats.tokens_launched = app_stats.tokens_launched.saturating_add(1);
+    app_stats.total_protocol_fees = app_stats.total_protocol_fees.saturating_add(fee_lamports);
+    emit_cpi!(TokenCreated {
+        mint: ctx.accounts.mint.key(),
+        creator: ctx.accounts.creator.key(),
+        app_stats: ctx.accounts.app_stats.key(),
+        decimals: ctx.accounts.mint.decimals,
+        supply: ctx.accounts.mint.supply,
+        curve_supply,
+        reserve_supply,
+        slot: Clock::get()?.slot,
+    });
     Ok(())
 }
@@ -1,10 +1,24 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{ prelude::*, solana_program::bpf_loader_upgradeable };
+use anchor_spl::token::spl_token::native_mint;
 
 use std::mem::size_of;
 
-use crate::{ AppStats, APP_STATS_SEED };
+use crate::{
+    error::SwapError,
+    AppStats,
+    CurveBounds,
+    PauseMode,
+    SupplyPolicy,
+    APP_STATS_SEED,
+    DEFAULT_GRADUATION_THRESHOLD,
+    FEE_PERCENTAGE,
+    MAX_HOOK_PROGRAMS,
+    MAX_QUOTE_MINTS,
+    ROOT_NAMESPACE,
+};
 
 #[derive(Accounts)]
+#[instruction(namespace: u64)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
@@ -14,10 +28,26 @@ pub struct Initialize<'info> {
         init,
         payer = owner,
         space = size_of::<AppStats>() + 8,
-        seeds = [APP_STATS_SEED],
+        seeds = [APP_STATS_SEED, namespace.to_le_bytes().as_ref()],
         bump
     )]
     pub app_stats: Box<Account<'info, AppStats>>,
+
+    // program data of this program, when the upgrade authority signs as owner
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID
+    )]
+    pub program_data: Option<Account<'info, ProgramData>>,
+
+    // root namespace app state, when its owner signs as owner
+    #[account(
+        seeds = [APP_STATS_SEED, ROOT_NAMESPACE.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub root_app_stats: Option<Box<Account<'info, AppStats>>>,
+
     // fee account
     /// CHECK:
     pub fee_account: AccountInfo<'info>,
@@ -26,13 +56,44 @@ pub struct Initialize<'info> {
 
 
 /* 
-    @dev initialize app state by owner.
+    @dev initialize app state by owner, who has to be the program upgrade authority or, for
+    any namespace but the root one, the root namespace owner.
+    @param namespace: launchpad id, one app state per namespace
     @param fee_larmports: sol amount as fee
 */
-pub fn handler(ctx: Context<Initialize>, fee_lamports: u64) -> Result<()> {
+pub fn handler(ctx: Context<Initialize>, namespace: u64, fee_lamports: u64) -> Result<()> {
+    let owner: Pubkey = ctx.accounts.owner.key();
+    let upgrade_authority: Option<Pubkey> = ctx.accounts.program_data
+        .as_ref()
+        .and_then(|program_data| program_data.upgrade_authority_address);
+    let root_owner: Option<Pubkey> = ctx.accounts.root_app_stats
+        .as_ref()
+        .filter(|_| namespace != ROOT_NAMESPACE)
+        .map(|root_app_stats| root_app_stats.owner);
+    require!(
+        upgrade_authority == Some(owner) || root_owner == Some(owner),
+        SwapError::NamespaceNotAuthorized
+    );
     let app_stats: &mut Box<Account<AppStats>> = &mut ctx.accounts.app_stats;
-    app_stats.owner = ctx.accounts.owner.key();
+    app_stats.namespace = namespace;
+    app_stats.owner = owner;
     app_stats.fee_account = ctx.accounts.fee_account.key();
     app_stats.fee_lamports = fee_lamports;
+    app_stats.pending_owner = Pubkey::default();
+    app_stats.fee_basis_points = FEE_PERCENTAGE;
+    app_stats.graduation_threshold = DEFAULT_GRADUATION_THRESHOLD;
+    app_stats.governance = Pubkey::default();
+    app_stats.pause_mode = PauseMode::Active;
+    app_stats.curve_bounds = CurveBounds::default();
+    // wsol is always allowed so the launchpad works before any quote mint is added
+    app_stats.quote_mints = [Pubkey::default(); MAX_QUOTE_MINTS];
+    app_stats.quote_mints[0] = native_mint::ID;
+    app_stats.quote_mint_count = 1;
+    app_stats.hook_programs = [Pubkey::default(); MAX_HOOK_PROGRAMS];
+    app_stats.hook_program_count = 0;
+    app_stats.supply_policy = SupplyPolicy::default();
+    app_stats.tokens_launched = 0;
+    app_stats.pairs_graduated = 0;
+    app_stats.total_protocol_fees = 0;
     Ok(())
 }
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use std::mem::size_of;
+
+use crate::{
+    error::SwapError,
+    AppStats,
+    Governance,
+    GovernanceParams,
+    GovernanceUpdated,
+    APP_STATS_SEED,
+    GOVERNANCE_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        has_one = owner @ SwapError::InvalidAppOwner,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // governance account
+    #[account(
+        init,
+        payer = owner,
+        space = size_of::<Governance>() + 8,
+        seeds = [GOVERNANCE_SEED, app_stats.key().as_ref()],
+        bump
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+    pub system_program: Program<'info, System>,
+}
+
+/*
+    @dev hand every owner-gated action over to a multisig with a timelock, can only be done once.
+    later signer set changes go through update_governance
+    @param params: signer set, approval threshold and timelock delay
+*/
+pub fn initialize_governance_handler(
+    ctx: Context<InitializeGovernance>,
+    params: GovernanceParams
+) -> Result<()> {
+    let governance: &mut Box<Account<Governance>> = &mut ctx.accounts.governance;
+    governance.app_stats = ctx.accounts.app_stats.key();
+    governance.proposal_count = 0;
+    governance.bump = ctx.bumps.governance;
+    governance.apply(&params)?;
+    ctx.accounts.app_stats.governance = governance.key();
+    emit_cpi!(GovernanceUpdated {
+        app_stats: governance.app_stats,
+        governance: governance.key(),
+        params,
+        config_version: governance.config_version,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
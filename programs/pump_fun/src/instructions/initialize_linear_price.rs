@@ -1,9 +1,29 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{ token::{ Mint, Token, TokenAccount }, token_interface };
+use anchor_spl::token_interface;
 
 use std::mem::size_of;
-use crate::{ SwapPair, AUTHORITY_SEED, SWAP_PAIR_SEED };
+use crate::{
+    curve::{ rescale_amount, LinearPriceCurve },
+    error::SwapError,
+    utils::check_transfer_hook,
+    AppStats,
+    PairInitialized,
+    PairStats,
+    PauseMode,
+    PriceObservations,
+    SwapPair,
+    TokenCreate,
+    APP_STATS_SEED,
+    AUTHORITY_SEED,
+    CURVE_QUOTE_DECIMALS,
+    PRICE_OBSERVATION_SEED,
+    QUOTE_ACCOUNT_SEED,
+    SWAP_PAIR_SEED,
+    TOKEN_ACCOUNT_SEED,
+    TOKEN_CREATE_SEED,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeLinearPrice<'info> {
     #[account(mut)]
@@ -21,30 +41,129 @@ pub struct InitializeLinearPrice<'info> {
     // mint address
     #[account(token::token_program = token_program_mint)]
     pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
-    // wsol mint address
-    pub wsol: Box<Account<'info, Mint>>,
+    // quote mint, has to be on the app state allowlist
+    #[account(
+        token::token_program = token_program_quote,
+        constraint = app_stats.is_quote_mint_allowed(&quote_mint.key()) @ SwapError::QuoteMintNotAllowed,
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     /// CHECK:
     #[account(seeds = [AUTHORITY_SEED, mint.key().as_ref()], bump)]
     pub pda: AccountInfo<'info>,
 
-    // token account for pda
+    // token account for pda, created by create_token
     #[account(
       mut,
+      seeds = [TOKEN_ACCOUNT_SEED, mint.key().as_ref()],
+      bump,
       token::mint = mint,
       token::authority = pda,
       token::token_program = token_program_mint,
     )]
     pub token_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    // wsol account for pda
-    #[account(mut, token::authority = pda, token::mint = wsol)]
-    pub token_native_for_pda: Box<Account<'info, TokenAccount>>,
+    // quote account for pda
+    #[account(
+        init,
+        payer = creator,
+        token::mint = quote_mint,
+        token::authority = pda,
+        token::token_program = token_program_quote,
+        seeds = [QUOTE_ACCOUNT_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub token_quote_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // price observations, written on every swap
+    #[account(
+        init,
+        payer = creator,
+        space = size_of::<PriceObservations>() + 8,
+        seeds = [PRICE_OBSERVATION_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub observation_state: AccountLoader<'info, PriceObservations>,
+
+    // token create account
+    #[account(
+        seeds = [TOKEN_CREATE_SEED, mint.key().as_ref()],
+        bump,
+        constraint = token_create.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+        constraint = token_create.creator == creator.key() @ SwapError::NotTokenCreator,
+    )]
+    pub token_create: Box<Account<'info, TokenCreate>>,
+
+    // app state account
+    #[account(
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
 
     pub system_program: Program<'info, System>,
     pub token_program_mint: Interface<'info, token_interface::TokenInterface>,
-    pub token_program: Program<'info, Token>,
+    pub token_program_quote: Interface<'info, token_interface::TokenInterface>,
 }
+
+impl<'info> InitializeLinearPrice<'info> {
+    /// Sets up the pair from a curve and graduation threshold written in CURVE_QUOTE_DECIMALS,
+    /// both are rescaled to the quote mint decimals before they are stored
+    pub fn init_pair(
+        &mut self,
+        curve: LinearPriceCurve,
+        graduation_threshold: u64,
+        bump: u8
+    ) -> Result<()> {
+        require!(self.app_stats.pause_mode.can_create(), SwapError::CreatePaused);
+        check_transfer_hook(&self.mint, &self.app_stats)?;
+        let quote_decimals: u8 = self.quote_mint.decimals;
+        let curve_supply: u64 = self.token_create.curve_supply;
+        let reserve_supply: u64 = self.token_create.reserve_supply;
+        // the vault has to hold both allocations recorded at creation
+        let allocated: u64 = curve_supply
+            .checked_add(reserve_supply)
+            .ok_or(SwapError::CalculationFailure)?;
+        require!(self.token_for_pda.amount >= allocated, SwapError::InvalidSupply);
+        let curve = curve.rescale(CURVE_QUOTE_DECIMALS, quote_decimals)?;
+        curve.validate(curve_supply)?;
+        let graduation_threshold: u64 = rescale_amount(
+            graduation_threshold,
+            CURVE_QUOTE_DECIMALS,
+            quote_decimals
+        )?;
+        // bump is used to sign for the vaults in every swap, so it has to derive the pda
+        let pda = Pubkey::create_program_address(
+            &[AUTHORITY_SEED, self.mint.key().as_ref(), &[bump]],
+            &crate::ID
+        ).map_err(|_| SwapError::InvalidProgramAddress)?;
+        require_keys_eq!(pda, self.pda.key(), SwapError::InvalidProgramAddress);
+        let pair: &mut Box<Account<SwapPair>> = &mut self.pair;
+        pair.token_account = self.token_for_pda.key();
+        pair.quote_account = self.token_quote_for_pda.key();
+        pair.mint = self.mint.key();
+        pair.quote_mint = self.quote_mint.key();
+        pair.curve = curve;
+        pair.bump = bump;
+        pair.pause_mode = PauseMode::Active;
+        pair.app_stats = self.app_stats.key();
+        pair.graduation_threshold = graduation_threshold;
+        pair.real_quote_reserves = 0;
+        pair.real_token_reserves = curve_supply;
+        pair.tokens_sold = 0;
+        pair.graduation_reserve = reserve_supply;
+        pair.stats = PairStats::default();
+        pair.observation_state = self.observation_state.key();
+        pair.graduated = false;
+        let price: u64 = pair.spot_price(self.mint.decimals)?;
+        let pair_key: Pubkey = pair.key();
+        self.observation_state
+            .load_init()?
+            .initialize(pair_key, Clock::get()?.unix_timestamp, price);
+        Ok(())
+    }
+}
+
 /*
     initialize linear bonding curve pool by token creator
     example curve - 0.5 slope (i.e. price increases by "1 base RLY per base CC" for every 2 display CC AKA 2e8 CC), starting price of 50 RLY at 300 (display) CC
@@ -53,23 +172,46 @@ pub struct InitializeLinearPrice<'info> {
     slope denominator
     initial token a price numerator : start price
     initial token a price denomiator
+    prices are written per wsol lamport and rescaled to the quote mint decimals
 */
-pub fn initialize_linear_price_handler(
-    ctx: Context<InitializeLinearPrice>,
+pub fn initialize_linear_price_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeLinearPrice<'info>>,
     slope_numerator: u64,
     slope_denominator: u64,
     initial_token_a_price_numerator: u64,
     initial_token_a_price_denominator: u64,
     bump: u8
 ) -> Result<()> {
-    let pair: &mut Box<Account<SwapPair>> = &mut ctx.accounts.pair;
-    pair.token_account = ctx.accounts.token_for_pda.key();
-    pair.native_account = ctx.accounts.token_native_for_pda.key();
-    pair.mint = ctx.accounts.mint.key();
-    pair.curve.slope_numerator = slope_numerator;
-    pair.curve.slope_denominator = slope_denominator;
-    pair.curve.initial_token_a_price_numerator = initial_token_a_price_numerator;
-    pair.curve.initial_token_a_price_denominator = initial_token_a_price_denominator;
-    pair.bump = bump;
+    let curve = LinearPriceCurve {
+        slope_numerator,
+        slope_denominator,
+        initial_token_a_price_numerator,
+        initial_token_a_price_denominator,
+    };
+    ctx.accounts.app_stats.curve_bounds.check(&curve)?;
+    let graduation_threshold: u64 = ctx.accounts.app_stats.graduation_threshold;
+    launch_pair(ctx, curve, graduation_threshold, bump)
+}
+
+/// Sets up the pair and emits PairInitialized, shared by custom and preset launches
+pub fn launch_pair<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeLinearPrice<'info>>,
+    curve: LinearPriceCurve,
+    graduation_threshold: u64,
+    bump: u8
+) -> Result<()> {
+    ctx.accounts.init_pair(curve, graduation_threshold, bump)?;
+    let pair: &Account<SwapPair> = &ctx.accounts.pair;
+    emit_cpi!(PairInitialized {
+        pair: pair.key(),
+        mint: pair.mint,
+        quote_mint: pair.quote_mint,
+        creator: ctx.accounts.creator.key(),
+        curve: pair.curve.clone(),
+        graduation_threshold: pair.graduation_threshold,
+        real_token_reserves: pair.real_token_reserves,
+        graduation_reserve: pair.graduation_reserve,
+        slot: Clock::get()?.slot,
+    });
     Ok(())
 }
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::SwapError, CurvePreset, CurveType, CURVE_PRESET_SEED };
+// glob import so the nested InitializeLinearPrice accounts bring their generated client modules
+use super::initialize_linear_price::*;
+
+#[derive(Accounts)]
+#[instruction(preset_id: u64)]
+pub struct InitializeLinearPriceWithPreset<'info> {
+    pub launch: InitializeLinearPrice<'info>,
+
+    // preset published by the app owner
+    #[account(
+        seeds = [
+            CURVE_PRESET_SEED,
+            launch.app_stats.key().as_ref(),
+            preset_id.to_le_bytes().as_ref(),
+        ],
+        bump = curve_preset.bump
+    )]
+    pub curve_preset: Box<Account<'info, CurvePreset>>,
+}
+
+/*
+    initialize linear bonding curve pool from an owner defined preset by token creator
+    @param
+    preset id: id of the curve preset under the pair's app state
+    bump: authority pda bump
+*/
+pub fn initialize_linear_price_with_preset_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeLinearPriceWithPreset<'info>>,
+    _preset_id: u64,
+    bump: u8
+) -> Result<()> {
+    let preset = ctx.accounts.curve_preset.params.clone();
+    require!(preset.enabled, SwapError::CurvePresetDisabled);
+    require!(preset.curve_type == CurveType::Linear, SwapError::UnsupportedCurveType);
+    // the preset curve was validated against its supply when it was published,
+    // init_pair validates it again once it is rescaled to the quote mint
+    require!(ctx.accounts.launch.token_create.curve_supply == preset.supply, SwapError::InvalidSupply);
+    // the nested launch accounts carry the event authority, so the pair is set up through their own context
+    let launch = Context::new(
+        ctx.program_id,
+        &mut ctx.accounts.launch,
+        ctx.remaining_accounts,
+        ctx.bumps.launch
+    );
+    launch_pair(launch, preset.curve, preset.graduation_threshold, bump)
+}
@@ -3,13 +3,53 @@ pub mod create_token;
 pub mod initialize_linear_price;
 pub mod swap_to_token;
 pub mod swap_to_sol;
+pub mod swap_to_token_native;
+pub mod swap_to_sol_native;
 pub mod proxy_initialize;
 pub mod create_account;
+pub mod update_config;
+pub mod propose_owner;
+pub mod accept_owner;
+pub mod initialize_governance;
+pub mod update_governance;
+pub mod create_proposal;
+pub mod approve_proposal;
+pub mod cancel_proposal;
+pub mod set_pause;
+pub mod set_pair_pause;
+pub mod initialize_linear_price_with_preset;
+pub mod set_curve_preset;
+pub mod set_curve_bounds;
+pub mod set_quote_mint;
+pub mod set_hook_program;
+pub mod set_supply_policy;
+pub mod skim;
+pub mod quote;
 
 pub use initialize::*;
 pub use create_token::*;
 pub use initialize_linear_price::*;
 pub use swap_to_token::*;
 pub use swap_to_sol::*;
+pub use swap_to_token_native::*;
+pub use swap_to_sol_native::*;
 pub use proxy_initialize::*;
 pub use create_account::*;
+pub use update_config::*;
+pub use propose_owner::*;
+pub use accept_owner::*;
+pub use initialize_governance::*;
+pub use update_governance::*;
+pub use create_proposal::*;
+pub use approve_proposal::*;
+pub use cancel_proposal::*;
+pub use set_pause::*;
+pub use set_pair_pause::*;
+pub use initialize_linear_price_with_preset::*;
+pub use set_curve_preset::*;
+pub use set_curve_bounds::*;
+pub use set_quote_mint::*;
+pub use set_hook_program::*;
+pub use set_supply_policy::*;
+pub use skim::*;
+pub use quote::*;
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    authorize_admin,
+    AdminAction,
+    AppStats,
+    Governance,
+    OwnerProposed,
+    Proposal,
+    APP_STATS_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for this exact owner
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+}
+
+/*
+    @dev first step of ownership transfer, only takes effect once the new owner accepts.
+    proposing the default pubkey cancels a pending transfer
+    @param new_owner: owner that can call accept_owner
+*/
+pub fn propose_owner_handler(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::ProposeOwner(new_owner)
+    )?;
+    ctx.accounts.app_stats.pending_owner = new_owner;
+    emit_cpi!(OwnerProposed {
+        app_stats: ctx.accounts.app_stats.key(),
+        authority: ctx.accounts.authority.key(),
+        pending_owner: new_owner,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken, token:: Token , token_2022::{self, spl_token_2022::instruction::AuthorityType, Burn, SetAuthority}, token_interface::{ Mint, TokenAccount, TokenInterface }
+    associated_token::AssociatedToken, token:: Token , token_2022::{self, Burn}, token_interface::{ Mint, TokenAccount, TokenInterface }
 };
 use raydium_cp_swap::{
     cpi,
@@ -8,8 +8,19 @@ use raydium_cp_swap::{
     states::{ AmmConfig, OBSERVATION_SEED, POOL_LP_MINT_SEED, POOL_SEED, POOL_VAULT_SEED },
 };
 
-use crate::{SwapPair, AUTHORITY_SEED};
+use crate::{
+    error::SwapError,
+    AppStats,
+    Graduated,
+    SwapPair,
+    TokenCreate,
+    APP_STATS_SEED,
+    AUTHORITY_SEED,
+    SWAP_PAIR_SEED,
+    TOKEN_CREATE_SEED,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ProxyInitialize<'info> {
     pub cp_swap_program: Program<'info, RaydiumCpSwap>,
@@ -66,12 +77,13 @@ pub struct ProxyInitialize<'info> {
     pub lp_mint: UncheckedAccount<'info>,
 
     /// CHECK:
-    #[account(seeds = [AUTHORITY_SEED, token_0_mint.key().as_ref()], bump)]
+    #[account(address = pair.authority()? @ SwapError::IncorrectSwapAccount)]
     pub pda: AccountInfo<'info>,
 
     /// payer token0 account
     #[account(
         mut,
+        address = pair.token_account @ SwapError::IncorrectSwapAccount,
         token::mint = token_0_mint,
         token::authority = pda,
     )]
@@ -80,8 +92,10 @@ pub struct ProxyInitialize<'info> {
     /// creator token1 account
     #[account(
         mut,
+        address = pair.quote_account @ SwapError::IncorrectSwapAccount,
         token::mint = token_1_mint,
         token::authority = pda,
+        constraint = creator_token_1.amount >= pair.graduation_threshold @ SwapError::GraduationThresholdNotReached,
     )]
     pub creator_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -134,8 +148,32 @@ pub struct ProxyInitialize<'info> {
     )]
     pub observation_state: UncheckedAccount<'info>,
 
+    /// bonding curve pair, marked graduated with its reserves zeroed
+    #[account(
+        mut,
+        seeds = [SWAP_PAIR_SEED, token_0_mint.key().as_ref()],
+        bump,
+        constraint = pair.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+        constraint = !pair.graduated @ SwapError::PairGraduated,
+    )]
     pub pair: Box<Account<'info, SwapPair>>,
 
+    /// launch record, its supply figures are updated with the burn and the deposit
+    #[account(
+        mut,
+        seeds = [TOKEN_CREATE_SEED, token_0_mint.key().as_ref()],
+        bump,
+    )]
+    pub token_create: Box<Account<'info, TokenCreate>>,
+
+    /// app state account
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
     /// Program to create mint account and mint tokens
     pub token_program: Program<'info, Token>,
     /// Spl token program or token program 2022
@@ -151,12 +189,6 @@ pub struct ProxyInitialize<'info> {
 }
 
 impl<'info> ProxyInitialize<'info> {
-    fn set_authority_ctx(&self) -> CpiContext<'info, 'info, 'info, 'info, SetAuthority<'info>> {
-        CpiContext::new(self.token_0_program.to_account_info(), SetAuthority {
-            current_authority: self.authority.to_account_info(),
-            account_or_mint: self.token_0_mint.to_account_info(),
-        })
-    }
     fn burn_ctx(&self) -> CpiContext<'info, 'info, 'info, 'info, Burn<'info>> {
         CpiContext::new(self.token_0_program.to_account_info(), Burn {
             mint: self.token_0_mint.to_account_info(),
@@ -167,30 +199,28 @@ impl<'info> ProxyInitialize<'info> {
 }
 
 /*
-    initialize raydium cp swap pool by token creator
+    initialize raydium cp swap pool once the pair reached its graduation threshold, the pair's
+    graduation reserve (or the tokens left on the curve when it kept none back) is deposited and
+    every other token in the vault is burned
     @param
-    init amount 0: token amount to deposit
     open time: swap open time
 */
 
-pub fn proxy_initialize_handler(
-    ctx: Context<ProxyInitialize>,
-    init_amount_0: u64,
-    open_time: u64
-) -> Result<()> {
+pub fn proxy_initialize_handler(ctx: Context<ProxyInitialize>, open_time: u64) -> Result<()> {
     let seeds: &[&[u8]; 3] = &[
         AUTHORITY_SEED,
         ctx.accounts.token_0_mint.to_account_info().key.as_ref(),
         &[ctx.accounts.pair.bump],
     ];
     let signer_seeds: &[&[&[u8]]; 1] = &[&seeds[..]];
-    let amount: u64 = ctx.accounts.creator_token_0.amount - init_amount_0;
+    let init_amount_0: u64 = ctx.accounts.pair.graduation_deposit();
+    require!(init_amount_0 > 0, SwapError::EmptySupply);
+    let amount: u64 = ctx.accounts.creator_token_0.amount
+        .checked_sub(init_amount_0)
+        .ok_or(SwapError::InvalidSupply)?;
     token_2022::burn(ctx.accounts.burn_ctx().with_signer(signer_seeds), amount)?;
-    token_2022::set_authority(
-        ctx.accounts.set_authority_ctx().with_signer(signer_seeds),
-        AuthorityType::MintTokens,
-        None
-    )?;
+    let tokens_sold: u64 = ctx.accounts.pair.tokens_sold;
+    ctx.accounts.token_create.record_graduation(tokens_sold, init_amount_0, amount)?;
     let init_amount_1: u64 = ctx.accounts.creator_token_1.amount;
     let cpi_accounts = cpi::accounts::Initialize {
         creator: ctx.accounts.creator.to_account_info(),
@@ -215,5 +245,17 @@ pub fn proxy_initialize_handler(
         rent: ctx.accounts.rent.to_account_info(),
     };
     let cpi_context = CpiContext::new(ctx.accounts.cp_swap_program.to_account_info(), cpi_accounts);
-    cpi::initialize(cpi_context, init_amount_0, init_amount_1, open_time)
+    cpi::initialize(cpi_context, init_amount_0, init_amount_1, open_time)?;
+    ctx.accounts.pair.record_graduation();
+    ctx.accounts.app_stats.pairs_graduated = ctx.accounts.app_stats.pairs_graduated.saturating_add(1);
+    emit_cpi!(Graduated {
+        mint: ctx.accounts.token_0_mint.key(),
+        quote_mint: ctx.accounts.token_1_mint.key(),
+        pool_state: ctx.accounts.pool_state.key(),
+        token_amount: init_amount_0,
+        quote_amount: init_amount_1,
+        burned: amount,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
 }
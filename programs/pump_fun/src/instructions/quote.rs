@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+use crate::{
+    curve::DepthLevel,
+    error::SwapError,
+    utils::{ amount_after_transfer_fee, amount_before_transfer_fee },
+    AppStats,
+    SwapPair,
+    APP_STATS_SEED,
+    MAX_DEPTH_LEVELS,
+    SWAP_PAIR_SEED,
+};
+
+/// Amounts of a swap, returned by the swap instructions and by the quote instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwapResult {
+    /// debited from the trader, quote on a buy and tokens on a sell, fees included
+    pub amount_in: u64,
+    /// credited to the trader after every fee
+    pub amount_out: u64,
+    /// sent to the treasury, in quote units
+    pub trade_fee: u64,
+    /// withheld by the quote mint's transfer fee on the curve leg
+    pub quote_transfer_fee: u64,
+    /// withheld by the token mint's transfer fee on the curve leg
+    pub token_transfer_fee: u64,
+    /// quote and tokens moved on the curve
+    pub curve_quote: u64,
+    pub curve_tokens: u64,
+}
+
+/// Amount that arrives when `gross_amount` quote is transferred, `quote_mint` is None
+/// for native sol swaps as wsol has no transfer fee
+fn quote_after_transfer_fee(
+    quote_mint: Option<&InterfaceAccount<token_interface::Mint>>,
+    gross_amount: u64
+) -> Result<u64> {
+    match quote_mint {
+        Some(quote_mint) => amount_after_transfer_fee(quote_mint, gross_amount),
+        None => Ok(gross_amount),
+    }
+}
+
+/// Quote amount to transfer so that `net_amount` arrives
+fn quote_before_transfer_fee(
+    quote_mint: Option<&InterfaceAccount<token_interface::Mint>>,
+    net_amount: u64
+) -> Result<u64> {
+    match quote_mint {
+        Some(quote_mint) => amount_before_transfer_fee(quote_mint, net_amount),
+        None => Ok(net_amount),
+    }
+}
+
+/// Buy of `amount_in` quote, trade fee and both mints' transfer fees included
+pub fn compute_buy(
+    pair: &SwapPair,
+    app_stats: &AppStats,
+    mint: &InterfaceAccount<token_interface::Mint>,
+    quote_mint: Option<&InterfaceAccount<token_interface::Mint>>,
+    amount_in: u64
+) -> Result<SwapResult> {
+    let trade_fee: u64 = app_stats.trade_fee(amount_in)?;
+    // the vault only receives what is left after the quote mint's transfer fee
+    let net_amount_in: u64 = quote_after_transfer_fee(quote_mint, amount_in - trade_fee)?;
+    let (curve_quote, curve_tokens) = pair.quote_buy(net_amount_in)?;
+    let amount_out: u64 = amount_after_transfer_fee(mint, curve_tokens)?;
+    let gross_amount_in: u64 = quote_before_transfer_fee(quote_mint, curve_quote)?;
+    // a buyout only takes part of the input, charge the fee on what the curve actually took
+    let trade_fee: u64 = if curve_quote < net_amount_in {
+        app_stats.trade_fee_on_top(gross_amount_in)?.min(trade_fee)
+    } else {
+        trade_fee
+    };
+    Ok(SwapResult {
+        amount_in: gross_amount_in + trade_fee,
+        amount_out,
+        trade_fee,
+        quote_transfer_fee: gross_amount_in - curve_quote,
+        token_transfer_fee: curve_tokens - amount_out,
+        curve_quote,
+        curve_tokens,
+    })
+}
+
+/// Sell of `amount_in` tokens, trade fee and both mints' transfer fees included
+pub fn compute_sell(
+    pair: &SwapPair,
+    app_stats: &AppStats,
+    mint: &InterfaceAccount<token_interface::Mint>,
+    quote_mint: Option<&InterfaceAccount<token_interface::Mint>>,
+    amount_in: u64
+) -> Result<SwapResult> {
+    // the vault only receives what is left after the mint's transfer fee
+    let net_amount_in: u64 = amount_after_transfer_fee(mint, amount_in)?;
+    let (curve_tokens, curve_quote) = pair.quote_sell(net_amount_in)?;
+    let trade_fee: u64 = app_stats.trade_fee(curve_quote)?;
+    let payout: u64 = curve_quote - trade_fee;
+    let amount_out: u64 = quote_after_transfer_fee(quote_mint, payout)?;
+    let gross_amount_in: u64 = amount_before_transfer_fee(mint, curve_tokens)?;
+    Ok(SwapResult {
+        amount_in: gross_amount_in,
+        amount_out,
+        trade_fee,
+        quote_transfer_fee: payout - amount_out,
+        token_transfer_fee: gross_amount_in - curve_tokens,
+        curve_quote,
+        curve_tokens,
+    })
+}
+
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    // pair account
+    #[account(
+        seeds = [SWAP_PAIR_SEED, mint.key().as_ref()],
+        bump,
+        constraint = pair.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+        constraint = !pair.graduated @ SwapError::PairGraduated,
+    )]
+    pub pair: Box<Account<'info, SwapPair>>,
+
+    // mint address
+    pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    // quote mint of the pair
+    #[account(address = pair.quote_mint)]
+    pub quote_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    // app state account
+    #[account(
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+}
+
+/*
+    quote a buy with the same math and pause checks as the swap instructions, meant to be simulated
+    @param amount_in: quote amount the trader would pay, fees included
+*/
+pub fn quote_buy_handler(ctx: Context<Quote>, amount_in: u64) -> Result<SwapResult> {
+    require!(
+        ctx.accounts.app_stats.pause_mode.can_buy() && ctx.accounts.pair.pause_mode.can_buy(),
+        SwapError::TradingPaused
+    );
+    compute_buy(
+        &ctx.accounts.pair,
+        &ctx.accounts.app_stats,
+        &ctx.accounts.mint,
+        Some(&ctx.accounts.quote_mint),
+        amount_in
+    )
+}
+
+/*
+    quote a sell with the same math and pause checks as the swap instructions, meant to be simulated
+    @param amount_in: token amount the trader would sell
+*/
+pub fn quote_sell_handler(ctx: Context<Quote>, amount_in: u64) -> Result<SwapResult> {
+    require!(
+        ctx.accounts.app_stats.pause_mode.can_sell() && ctx.accounts.pair.pause_mode.can_sell(),
+        SwapError::TradingPaused
+    );
+    compute_sell(
+        &ctx.accounts.pair,
+        &ctx.accounts.app_stats,
+        &ctx.accounts.mint,
+        Some(&ctx.accounts.quote_mint),
+        amount_in
+    )
+}
+
+/*
+    current price of one whole token in quote base units
+*/
+pub fn get_spot_price_handler(ctx: Context<Quote>) -> Result<u64> {
+    ctx.accounts.pair.spot_price(ctx.accounts.mint.decimals)
+}
+
+/*
+    fully diluted market cap, the current mint supply at the spot price in quote base units
+*/
+pub fn get_market_cap_handler(ctx: Context<Quote>) -> Result<u64> {
+    ctx.accounts.pair.fully_diluted_market_cap(ctx.accounts.mint.supply)
+}
+
+/*
+    circulating market cap, the tokens bought off the curve at the spot price in quote base units
+*/
+pub fn get_circulating_market_cap_handler(ctx: Context<Quote>) -> Result<u64> {
+    ctx.accounts.pair.circulating_market_cap()
+}
+
+/*
+    quote the curve needs to sell every token it has left, before fees
+*/
+pub fn get_buyout_cost_handler(ctx: Context<Quote>) -> Result<u64> {
+    ctx.accounts.pair.buyout_cost()
+}
+
+/*
+    spot price after every step of the curve allocation is sold
+    @param step_bps: share of the curve allocation between levels, in basis points
+*/
+pub fn get_depth_table_handler(ctx: Context<Quote>, step_bps: u16) -> Result<Vec<DepthLevel>> {
+    let table = ctx.accounts.pair.depth_table(step_bps, ctx.accounts.mint.decimals)?;
+    require!(table.len() <= MAX_DEPTH_LEVELS, SwapError::InvalidInput);
+    Ok(table)
+}
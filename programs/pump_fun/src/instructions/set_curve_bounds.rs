@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    authorize_admin,
+    AdminAction,
+    AppStats,
+    CurveBounds,
+    CurveBoundsSet,
+    Governance,
+    Proposal,
+    APP_STATS_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCurveBounds<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for these exact bounds
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+}
+
+/*
+    @dev set the bounds for launches with custom curve parameters.
+    @param bounds: allowed slope and initial price ranges, or allow_custom false for presets only
+*/
+pub fn set_curve_bounds_handler(ctx: Context<SetCurveBounds>, bounds: CurveBounds) -> Result<()> {
+    bounds.validate()?;
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::SetCurveBounds(bounds)
+    )?;
+    ctx.accounts.app_stats.curve_bounds = bounds;
+    emit_cpi!(CurveBoundsSet {
+        app_stats: ctx.accounts.app_stats.key(),
+        authority: ctx.accounts.authority.key(),
+        bounds,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
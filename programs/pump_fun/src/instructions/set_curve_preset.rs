@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use std::mem::size_of;
+
+use crate::{
+    authorize_admin,
+    AdminAction,
+    AppStats,
+    CurvePreset,
+    CurvePresetParams,
+    CurvePresetSet,
+    Governance,
+    Proposal,
+    APP_STATS_SEED,
+    CURVE_PRESET_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(preset_id: u64)]
+pub struct SetCurvePreset<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // preset account
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = size_of::<CurvePreset>() + 8,
+        seeds = [CURVE_PRESET_SEED, app_stats.key().as_ref(), preset_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub curve_preset: Box<Account<'info, CurvePreset>>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for this exact preset
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/*
+    @dev publish or replace a curve preset creators can launch with.
+    @param preset_id: id of the preset under the app state
+    @param params: curve type, parameters, supply and graduation threshold
+*/
+pub fn set_curve_preset_handler(
+    ctx: Context<SetCurvePreset>,
+    preset_id: u64,
+    params: CurvePresetParams
+) -> Result<()> {
+    params.curve.validate(params.supply)?;
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::SetCurvePreset(preset_id, params.clone())
+    )?;
+    let curve_preset: &mut Box<Account<CurvePreset>> = &mut ctx.accounts.curve_preset;
+    curve_preset.app_stats = ctx.accounts.app_stats.key();
+    curve_preset.id = preset_id;
+    curve_preset.params = params.clone();
+    curve_preset.bump = ctx.bumps.curve_preset;
+    emit_cpi!(CurvePresetSet {
+        app_stats: curve_preset.app_stats,
+        authority: ctx.accounts.authority.key(),
+        curve_preset: curve_preset.key(),
+        preset_id,
+        params,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
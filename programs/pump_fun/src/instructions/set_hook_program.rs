@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    authorize_admin,
+    AdminAction,
+    AppStats,
+    Governance,
+    HookProgramSet,
+    Proposal,
+    APP_STATS_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetHookProgram<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    /// CHECK: transfer hook program, only its key is stored
+    #[account(executable)]
+    pub hook_program: AccountInfo<'info>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for this exact change
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+}
+
+/*
+    @dev add a program to or remove it from the transfer hook allowlist.
+    @param allowed: true to allow launching and trading mints that use the hook
+*/
+pub fn set_hook_program_handler(ctx: Context<SetHookProgram>, allowed: bool) -> Result<()> {
+    let hook_program: Pubkey = ctx.accounts.hook_program.key();
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::SetHookProgram(hook_program, allowed)
+    )?;
+    ctx.accounts.app_stats.set_hook_program(hook_program, allowed)?;
+    emit_cpi!(HookProgramSet {
+        app_stats: ctx.accounts.app_stats.key(),
+        authority: ctx.accounts.authority.key(),
+        hook_program,
+        allowed,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
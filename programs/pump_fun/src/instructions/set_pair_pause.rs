@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    authorize_admin,
+    error::SwapError,
+    AdminAction,
+    AppStats,
+    Governance,
+    PauseChanged,
+    PauseMode,
+    Proposal,
+    SwapPair,
+    APP_STATS_SEED,
+    SWAP_PAIR_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPairPause<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // pair account
+    #[account(
+        mut,
+        seeds = [SWAP_PAIR_SEED, pair.mint.as_ref()],
+        bump,
+        constraint = pair.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+    )]
+    pub pair: Box<Account<'info, SwapPair>>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for this exact pair and mode
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+}
+
+/*
+    @dev set the circuit breaker of a single pair.
+    @param mode: new pause mode
+*/
+pub fn set_pair_pause_handler(ctx: Context<SetPairPause>, mode: PauseMode) -> Result<()> {
+    let mint: Pubkey = ctx.accounts.pair.mint;
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::SetPairPause(mint, mode)
+    )?;
+    let previous_mode = ctx.accounts.pair.pause_mode;
+    ctx.accounts.pair.pause_mode = mode;
+    emit_cpi!(PauseChanged {
+        mint: Some(mint),
+        previous_mode,
+        mode,
+    });
+    Ok(())
+}
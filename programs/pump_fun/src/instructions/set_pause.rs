@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    authorize_admin,
+    AdminAction,
+    AppStats,
+    Governance,
+    PauseChanged,
+    PauseMode,
+    Proposal,
+    APP_STATS_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for this exact mode
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+}
+
+/*
+    @dev set the global circuit breaker for every pair and launch.
+    @param mode: new pause mode
+*/
+pub fn set_pause_handler(ctx: Context<SetPause>, mode: PauseMode) -> Result<()> {
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::SetPause(mode)
+    )?;
+    let previous_mode = ctx.accounts.app_stats.pause_mode;
+    ctx.accounts.app_stats.pause_mode = mode;
+    emit_cpi!(PauseChanged {
+        mint: None,
+        previous_mode,
+        mode,
+    });
+    Ok(())
+}
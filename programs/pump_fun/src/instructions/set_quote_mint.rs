@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+
+use crate::{
+    authorize_admin,
+    AdminAction,
+    AppStats,
+    Governance,
+    Proposal,
+    QuoteMintSet,
+    APP_STATS_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetQuoteMint<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // quote mint, spl token or token-2022
+    pub quote_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for this exact change
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+}
+
+/*
+    @dev add a mint to or remove it from the quote allowlist.
+    @param allowed: true to allow new pairs against the mint, false to stop new launches against it
+*/
+pub fn set_quote_mint_handler(ctx: Context<SetQuoteMint>, allowed: bool) -> Result<()> {
+    let quote_mint: Pubkey = ctx.accounts.quote_mint.key();
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::SetQuoteMint(quote_mint, allowed)
+    )?;
+    ctx.accounts.app_stats.set_quote_mint(quote_mint, allowed)?;
+    emit_cpi!(QuoteMintSet {
+        app_stats: ctx.accounts.app_stats.key(),
+        authority: ctx.accounts.authority.key(),
+        quote_mint,
+        allowed,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
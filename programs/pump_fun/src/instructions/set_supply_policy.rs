@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    authorize_admin,
+    AdminAction,
+    AppStats,
+    Governance,
+    Proposal,
+    SupplyPolicy,
+    SupplyPolicySet,
+    APP_STATS_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetSupplyPolicy<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for this exact policy
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+}
+
+/*
+    @dev set the supply cap, decimals range and graduation reserve for new launches.
+    @param policy: applies to tokens created afterwards, launched tokens keep their split
+*/
+pub fn set_supply_policy_handler(ctx: Context<SetSupplyPolicy>, policy: SupplyPolicy) -> Result<()> {
+    policy.validate()?;
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::SetSupplyPolicy(policy)
+    )?;
+    ctx.accounts.app_stats.supply_policy = policy;
+    emit_cpi!(SupplyPolicySet {
+        app_stats: ctx.accounts.app_stats.key(),
+        authority: ctx.accounts.authority.key(),
+        policy,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
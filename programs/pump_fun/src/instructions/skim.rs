@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{ transfer, Transfer },
+    token_2022::{ transfer_checked, TransferChecked, ID },
+    token_interface,
+};
+
+use crate::{
+    error::SwapError,
+    utils::{ check_transfer_hook, transfer_checked_with_hook },
+    AppStats,
+    SwapPair,
+    APP_STATS_SEED,
+    AUTHORITY_SEED,
+    SWAP_PAIR_SEED,
+};
+
+#[derive(Accounts)]
+pub struct Skim<'info> {
+    /// CHECK:
+    #[account(address = pair.authority()? @ SwapError::IncorrectSwapAccount)]
+    pub pda: AccountInfo<'info>,
+
+    #[account(token::token_program = token_program_mint)]
+    pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    #[account(address = pair.quote_mint @ SwapError::IncorrectSwapAccount)]
+    pub quote_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    // pair account
+    #[account(
+      seeds = [SWAP_PAIR_SEED, mint.key().as_ref()],
+      bump,
+      constraint = pair.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+      constraint = !pair.graduated @ SwapError::PairGraduated,
+    )]
+    pub pair: Box<Account<'info, SwapPair>>,
+
+    // token account for pda
+    #[account(mut, address = pair.token_account @ SwapError::IncorrectSwapAccount)]
+    pub token_account_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // quote account for pda
+    #[account(mut, address = pair.quote_account @ SwapError::IncorrectSwapAccount)]
+    pub quote_account_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // treasury quote account, owned by the app state fee wallet
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = app_stats.fee_account,
+        token::token_program = token_program_quote,
+    )]
+    pub fee_quote_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // treasury token account, owned by the app state fee wallet
+    #[account(
+      mut,
+      token::mint = mint,
+      token::authority = app_stats.fee_account,
+      token::token_program = token_program_mint,
+    )]
+    pub fee_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // app state account
+    #[account(
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    pub token_program_quote: Interface<'info, token_interface::TokenInterface>,
+    pub token_program_mint: Interface<'info, token_interface::TokenInterface>,
+}
+
+impl<'info> Skim<'info> {
+    fn to_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts: Transfer = Transfer {
+            from: self.token_account_for_pda.to_account_info(),
+            to: self.fee_token_account.to_account_info(),
+            authority: self.pda.to_account_info(),
+        };
+        CpiContext::new(self.token_program_mint.to_account_info(), cpi_accounts)
+    }
+
+    fn transfer_token_checked(
+        &self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]]
+    ) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program_mint.to_account_info(),
+            self.token_account_for_pda.to_account_info(),
+            &self.mint,
+            self.fee_token_account.to_account_info(),
+            self.pda.to_account_info(),
+            hook_accounts,
+            amount,
+            signer_seeds
+        )
+    }
+
+    fn to_transfer_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts: TransferChecked = TransferChecked {
+            from: self.quote_account_for_pda.to_account_info(),
+            to: self.fee_quote_account.to_account_info(),
+            authority: self.pda.to_account_info(),
+            mint: self.quote_mint.to_account_info(),
+        };
+        CpiContext::new(self.token_program_quote.to_account_info(), cpi_accounts)
+    }
+}
+
+/*
+    send vault balances above the pair's internal reserves to the treasury, can be called by anyone.
+    these are tokens or quote transferred straight into the vaults, which the curve ignores
+*/
+pub fn skim_handler<'info>(ctx: Context<'_, '_, '_, 'info, Skim<'info>>) -> Result<()> {
+    let token_surplus: u64 = ctx.accounts.token_account_for_pda.amount
+        .saturating_sub(ctx.accounts.pair.real_token_reserves)
+        .saturating_sub(ctx.accounts.pair.graduation_reserve);
+    let quote_surplus: u64 = ctx.accounts.quote_account_for_pda.amount
+        .saturating_sub(ctx.accounts.pair.real_quote_reserves);
+    let seeds: &[&[u8]; 3] = &[
+        AUTHORITY_SEED,
+        ctx.accounts.mint.to_account_info().key.as_ref(),
+        &[ctx.accounts.pair.bump],
+    ];
+    let signer_seeds: &[&[&[u8]]; 1] = &[&seeds[..]];
+    if token_surplus > 0 {
+        if ctx.accounts.token_program_mint.key() == ID {
+            check_transfer_hook(&ctx.accounts.mint, &ctx.accounts.app_stats)?;
+            ctx.accounts.transfer_token_checked(token_surplus, ctx.remaining_accounts, signer_seeds)?;
+        } else {
+            transfer(ctx.accounts.to_transfer_context().with_signer(signer_seeds), token_surplus)?;
+        }
+    }
+    if quote_surplus > 0 {
+        transfer_checked(
+            ctx.accounts.to_transfer_quote_context().with_signer(signer_seeds),
+            quote_surplus,
+            ctx.accounts.quote_mint.decimals
+        )?;
+    }
+    Ok(())
+}
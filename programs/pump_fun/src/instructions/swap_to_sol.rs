@@ -1,31 +1,59 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    token::{ transfer, Mint, Token, TokenAccount, Transfer },
+    token::{ transfer, Transfer },
     token_2022::{ transfer_checked, TransferChecked, ID },
-    token_interface,
+    token_interface::{ self, close_account as close_token_account, CloseAccount as CloseTokenAccount },
 };
 
-use crate::{ curve::{ to_u128, to_u64 }, error::SwapError, AppStats, SwapPair, APP_STATS_SEED, AUTHORITY_SEED, DENOMINATOR, FEE_PERCENTAGE };
+use crate::{
+    compute_sell,
+    error::SwapError,
+    utils::{
+        check_transfer_hook,
+        get_withheld_transfer_fees,
+        harvest_withheld_transfer_fees,
+        transfer_checked_with_hook,
+    },
+    AppStats,
+    PriceObservations,
+    SwapPair,
+    SwapResult,
+    Trade,
+    TradeSide,
+    APP_STATS_SEED,
+    AUTHORITY_SEED,
+    SWAP_PAIR_SEED,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SwapToSol<'info> {
     #[account(mut)]
     pub swapper: Signer<'info>,
 
     /// CHECK:
+    #[account(address = pair.authority()? @ SwapError::IncorrectSwapAccount)]
     pub pda: AccountInfo<'info>,
 
-    #[account(token::token_program = token_program_mint)]
+    // mutable so transfer fees withheld in an emptied token account can be harvested to it
+    #[account(mut, token::token_program = token_program_mint)]
     pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
-    pub wsol: Box<Account<'info, Mint>>,
+    #[account(address = pair.quote_mint @ SwapError::IncorrectSwapAccount)]
+    pub quote_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     #[account(
       mut,
-      constraint = pair.mint == mint.key(),
-      constraint = pair.token_account == token_account_for_pda.key(),
+      seeds = [SWAP_PAIR_SEED, mint.key().as_ref()],
+      bump,
+      constraint = pair.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+      constraint = !pair.graduated @ SwapError::PairGraduated,
     )]
     pub pair: Box<Account<'info, SwapPair>>,
 
+    // price observations of the pair
+    #[account(mut, address = pair.observation_state @ SwapError::IncorrectSwapAccount)]
+    pub observation_state: AccountLoader<'info, PriceObservations>,
+
     // token account for swapper
     #[account(
       mut,
@@ -34,48 +62,59 @@ pub struct SwapToSol<'info> {
     )]
     pub token_account_for_swapper: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    // wol account for swapper
+    // quote account for swapper
     #[account(
       mut,
-      constraint = native_account_for_swapper.is_native() == true,
+      token::mint = quote_mint,
+      token::token_program = token_program_quote,
     )]
-    pub native_account_for_swapper: Box<Account<'info, TokenAccount>>,
+    pub quote_account_for_swapper: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     // token account for pda
     #[account(
       mut,
+      address = pair.token_account @ SwapError::IncorrectSwapAccount,
       token::token_program = token_program_mint,
-      token::authority = pda,
     )]
     pub token_account_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    // wsol account for pda
-    #[account(
-      mut,
-      token::authority = pda,
-      constraint = native_account_for_pda.is_native() == true,
-    )]
-    pub native_account_for_pda: Box<Account<'info, TokenAccount>>,
+    // quote account for pda
+    #[account(mut, address = pair.quote_account @ SwapError::IncorrectSwapAccount)]
+    pub quote_account_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    // fee account
+    // treasury quote account, owned by the app state fee wallet
     #[account(
-        mut, 
-        constraint = app_stats.fee_account == fee_account.key()
+        mut,
+        token::mint = quote_mint,
+        token::authority = app_stats.fee_account,
+        token::token_program = token_program_quote,
     )]
-    pub fee_account: Box<Account<'info, TokenAccount>>,
+    pub fee_quote_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     // app state
     #[account(
-        seeds = [APP_STATS_SEED],
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
         bump
     )]
     pub app_stats: Box<Account<'info, AppStats>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program_quote: Interface<'info, token_interface::TokenInterface>,
     pub token_program_mint: Interface<'info, token_interface::TokenInterface>,
 }
 
 impl<'info> SwapToSol<'info> {
+    fn close_swapper_account_context(
+        &self
+    ) -> CpiContext<'_, '_, '_, 'info, CloseTokenAccount<'info>> {
+        let cpi_accounts: CloseTokenAccount = CloseTokenAccount {
+            account: self.token_account_for_swapper.to_account_info(),
+            destination: self.swapper.to_account_info(),
+            authority: self.swapper.to_account_info(),
+        };
+        CpiContext::new(self.token_program_mint.to_account_info(), cpi_accounts)
+    }
+
     fn to_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         let cpi_accounts: Transfer = Transfer {
             from: self.token_account_for_swapper.to_account_info().clone(),
@@ -85,66 +124,129 @@ impl<'info> SwapToSol<'info> {
         CpiContext::new(self.token_program_mint.to_account_info(), cpi_accounts)
     }
 
-    pub fn transfer_checked_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
-        let cpi_program: AccountInfo = self.token_program_mint.to_account_info();
-        let cpi_accounts: TransferChecked = TransferChecked {
-            from: self.token_account_for_swapper.to_account_info(),
-            to: self.token_account_for_pda.to_account_info(),
-            authority: self.swapper.to_account_info(),
-            mint: self.mint.to_account_info(),
-        };
-
-        CpiContext::new(cpi_program, cpi_accounts)
+    fn transfer_token_checked(
+        &self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]]
+    ) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program_mint.to_account_info(),
+            self.token_account_for_swapper.to_account_info(),
+            &self.mint,
+            self.token_account_for_pda.to_account_info(),
+            self.swapper.to_account_info(),
+            hook_accounts,
+            amount,
+            signer_seeds
+        )
     }
 
-    fn to_transfer_native_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts: Transfer = Transfer {
-            from: self.native_account_for_pda.to_account_info().clone(),
-            to: self.native_account_for_swapper.to_account_info().clone(),
-            authority: self.pda.to_account_info().clone(),
+    fn to_transfer_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts: TransferChecked = TransferChecked {
+            from: self.quote_account_for_pda.to_account_info(),
+            to: self.quote_account_for_swapper.to_account_info(),
+            authority: self.pda.to_account_info(),
+            mint: self.quote_mint.to_account_info(),
         };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+        CpiContext::new(self.token_program_quote.to_account_info(), cpi_accounts)
     }
 
-    fn to_transfer_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts: Transfer = Transfer {
-            from: self.native_account_for_pda.to_account_info().clone(),
-            to: self.fee_account.to_account_info().clone(),
-            authority: self.pda.to_account_info().clone(),
+    fn to_transfer_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts: TransferChecked = TransferChecked {
+            from: self.quote_account_for_pda.to_account_info(),
+            to: self.fee_quote_account.to_account_info(),
+            authority: self.pda.to_account_info(),
+            mint: self.quote_mint.to_account_info(),
         };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+        CpiContext::new(self.token_program_quote.to_account_info(), cpi_accounts)
     }
 }
 /*
-    sell token for sol by investor
+    sell token for the pair's quote by investor
+    transfer fees of token-2022 mints are taken out before the curve sees an amount
     @param
     amount_in: token amount to sell
+    minimum_amount_out: least quote the investor has to receive after fees
+    close_if_empty: close the investor's token account when this sell empties it, returning the rent
+    returns the amounts actually swapped as return data
 */
-pub fn swap_to_sol_handler(ctx: Context<SwapToSol>, amount_in: u64) -> Result<()> {
-    let source_amount: u128 = to_u128(amount_in)?;
-    let swap_source_amount = to_u128(ctx.accounts.token_account_for_pda.amount)?;
-    let swap_destination_amount = to_u128(ctx.accounts.native_account_for_pda.amount)?;
-    let (source_amount, destination_amount) = ctx.accounts.pair.curve
-        .swap_b_to_a(source_amount, swap_source_amount, swap_destination_amount)
-        .ok_or(SwapError::ZeroTradingTokens)?;
+pub fn swap_to_sol_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapToSol<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    close_if_empty: bool
+) -> Result<SwapResult> {
+    require!(
+        ctx.accounts.app_stats.pause_mode.can_sell() && ctx.accounts.pair.pause_mode.can_sell(),
+        SwapError::TradingPaused
+    );
+    let result: SwapResult = compute_sell(
+        &ctx.accounts.pair,
+        &ctx.accounts.app_stats,
+        &ctx.accounts.mint,
+        Some(&ctx.accounts.quote_mint),
+        amount_in
+    )?;
+    require!(result.amount_out >= minimum_amount_out, SwapError::ExceededSlippage);
+    ctx.accounts.pair.record_sell(result.curve_tokens, result.curve_quote)?;
     if ctx.accounts.token_program_mint.key() == ID {
-        transfer_checked(
-            ctx.accounts.transfer_checked_ctx(),
-            to_u64(source_amount)?,
-            ctx.accounts.mint.decimals
-        )?;
+        check_transfer_hook(&ctx.accounts.mint, &ctx.accounts.app_stats)?;
+        ctx.accounts.transfer_token_checked(result.amount_in, ctx.remaining_accounts, &[])?;
     } else {
-        transfer(ctx.accounts.to_transfer_context(), to_u64(source_amount)?)?;
+        transfer(ctx.accounts.to_transfer_context(), result.amount_in)?;
+    }
+    ctx.accounts.token_account_for_swapper.reload()?;
+    if close_if_empty && ctx.accounts.token_account_for_swapper.amount == 0 {
+        // token-2022 won't close an account that still holds withheld transfer fees
+        if get_withheld_transfer_fees(&ctx.accounts.token_account_for_swapper)? > 0 {
+            harvest_withheld_transfer_fees(
+                &ctx.accounts.token_program_mint.to_account_info(),
+                &ctx.accounts.mint,
+                &ctx.accounts.token_account_for_swapper
+            )?;
+        }
+        close_token_account(ctx.accounts.close_swapper_account_context())?;
     }
     let seeds: &[&[u8]; 3] = &[
         AUTHORITY_SEED,
         ctx.accounts.mint.to_account_info().key.as_ref(),
         &[ctx.accounts.pair.bump],
     ];
-    let fee_amount: u64 = to_u64(destination_amount)? / DENOMINATOR as u64 * FEE_PERCENTAGE as u64;
-    let amount: u64 = to_u64(destination_amount)? - fee_amount;
     let signer_seeds: &[&[&[u8]]; 1] = &[&seeds[..]];
-    transfer(ctx.accounts.to_transfer_native_context().with_signer(signer_seeds), amount)?;
-    transfer(ctx.accounts.to_transfer_fee_context().with_signer(signer_seeds), fee_amount)?;
-    Ok(())
+    let payout: u64 = result.curve_quote - result.trade_fee;
+    let quote_decimals: u8 = ctx.accounts.quote_mint.decimals;
+    transfer_checked(
+        ctx.accounts.to_transfer_quote_context().with_signer(signer_seeds),
+        payout,
+        quote_decimals
+    )?;
+    transfer_checked(
+        ctx.accounts.to_transfer_fee_context().with_signer(signer_seeds),
+        result.trade_fee,
+        quote_decimals
+    )?;
+    let trade = Trade {
+        side: TradeSide::Sell,
+        mint: ctx.accounts.mint.key(),
+        quote_mint: ctx.accounts.pair.quote_mint,
+        trader: ctx.accounts.swapper.key(),
+        amount_in: result.amount_in,
+        amount_out: result.amount_out,
+        treasury: ctx.accounts.app_stats.fee_account,
+        treasury_fee: result.trade_fee,
+        quote_transfer_fee: result.quote_transfer_fee,
+        token_transfer_fee: result.token_transfer_fee,
+        real_quote_reserves: ctx.accounts.pair.real_quote_reserves,
+        real_token_reserves: ctx.accounts.pair.real_token_reserves,
+        spot_price: ctx.accounts.pair.spot_price(ctx.accounts.mint.decimals)?,
+        slot: Clock::get()?.slot,
+    };
+    ctx.accounts.pair.stats.record_trade(&trade, result.curve_quote, result.curve_tokens);
+    ctx.accounts.app_stats.record_trade_fee(&trade.quote_mint, result.trade_fee);
+    ctx.accounts.observation_state
+        .load_mut()?
+        .record(Clock::get()?.unix_timestamp, trade.spot_price);
+    emit_cpi!(trade);
+    Ok(result)
 }
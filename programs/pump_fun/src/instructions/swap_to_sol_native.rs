@@ -0,0 +1,268 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{
+        close_account,
+        spl_token::native_mint,
+        transfer,
+        CloseAccount,
+        Mint,
+        Token,
+        TokenAccount,
+        Transfer,
+    },
+    token_2022::ID,
+    token_interface::{ self, close_account as close_token_account, CloseAccount as CloseTokenAccount },
+};
+
+use crate::{
+    compute_sell,
+    error::SwapError,
+    utils::{
+        check_transfer_hook,
+        get_withheld_transfer_fees,
+        harvest_withheld_transfer_fees,
+        transfer_checked_with_hook,
+    },
+    AppStats,
+    PriceObservations,
+    SwapPair,
+    SwapResult,
+    Trade,
+    TradeSide,
+    APP_STATS_SEED,
+    AUTHORITY_SEED,
+    SWAP_PAIR_SEED,
+    UNWRAP_ACCOUNT_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapToSolNative<'info> {
+    #[account(mut)]
+    pub swapper: Signer<'info>,
+
+    /// CHECK:
+    #[account(address = pair.authority()? @ SwapError::IncorrectSwapAccount)]
+    pub pda: AccountInfo<'info>,
+
+    // mutable so transfer fees withheld in an emptied token account can be harvested to it
+    #[account(mut, token::token_program = token_program_mint)]
+    pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+    #[account(address = native_mint::ID)]
+    pub wsol: Box<Account<'info, Mint>>,
+
+    #[account(
+      mut,
+      seeds = [SWAP_PAIR_SEED, mint.key().as_ref()],
+      bump,
+      constraint = pair.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+      constraint = !pair.graduated @ SwapError::PairGraduated,
+      constraint = pair.quote_mint == native_mint::ID @ SwapError::NativeQuoteRequired,
+    )]
+    pub pair: Box<Account<'info, SwapPair>>,
+
+    // price observations of the pair
+    #[account(mut, address = pair.observation_state @ SwapError::IncorrectSwapAccount)]
+    pub observation_state: AccountLoader<'info, PriceObservations>,
+
+    // token account for swapper
+    #[account(
+      mut,
+      token::mint = mint,
+      token::token_program = token_program_mint,
+    )]
+    pub token_account_for_swapper: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // temporary wsol account the payout is unwrapped through, closed to the swapper
+    #[account(
+        init,
+        payer = swapper,
+        token::mint = wsol,
+        token::authority = pda,
+        token::token_program = token_program,
+        seeds = [UNWRAP_ACCOUNT_SEED, swapper.key().as_ref()],
+        bump
+    )]
+    pub unwrap_account: Box<Account<'info, TokenAccount>>,
+
+    // token account for pda
+    #[account(
+      mut,
+      address = pair.token_account @ SwapError::IncorrectSwapAccount,
+      token::token_program = token_program_mint,
+    )]
+    pub token_account_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // wsol quote account for pda
+    #[account(mut, address = pair.quote_account @ SwapError::IncorrectSwapAccount)]
+    pub quote_account_for_pda: Box<Account<'info, TokenAccount>>,
+
+    // treasury wsol account, owned by the app state fee wallet
+    #[account(
+        mut,
+        token::authority = app_stats.fee_account,
+        constraint = fee_quote_account.is_native() @ SwapError::IncorrectFeeAccount,
+    )]
+    pub fee_quote_account: Box<Account<'info, TokenAccount>>,
+
+    // app state
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_program_mint: Interface<'info, token_interface::TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapToSolNative<'info> {
+    fn close_swapper_account_context(
+        &self
+    ) -> CpiContext<'_, '_, '_, 'info, CloseTokenAccount<'info>> {
+        let cpi_accounts: CloseTokenAccount = CloseTokenAccount {
+            account: self.token_account_for_swapper.to_account_info(),
+            destination: self.swapper.to_account_info(),
+            authority: self.swapper.to_account_info(),
+        };
+        CpiContext::new(self.token_program_mint.to_account_info(), cpi_accounts)
+    }
+
+    fn to_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts: Transfer = Transfer {
+            from: self.token_account_for_swapper.to_account_info(),
+            to: self.token_account_for_pda.to_account_info(),
+            authority: self.swapper.to_account_info(),
+        };
+        CpiContext::new(self.token_program_mint.to_account_info(), cpi_accounts)
+    }
+
+    fn transfer_token_checked(
+        &self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]]
+    ) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program_mint.to_account_info(),
+            self.token_account_for_swapper.to_account_info(),
+            &self.mint,
+            self.token_account_for_pda.to_account_info(),
+            self.swapper.to_account_info(),
+            hook_accounts,
+            amount,
+            signer_seeds
+        )
+    }
+
+    fn to_transfer_unwrap_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts: Transfer = Transfer {
+            from: self.quote_account_for_pda.to_account_info(),
+            to: self.unwrap_account.to_account_info(),
+            authority: self.pda.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn to_transfer_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts: Transfer = Transfer {
+            from: self.quote_account_for_pda.to_account_info(),
+            to: self.fee_quote_account.to_account_info(),
+            authority: self.pda.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    fn close_unwrap_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts: CloseAccount = CloseAccount {
+            account: self.unwrap_account.to_account_info(),
+            destination: self.swapper.to_account_info(),
+            authority: self.pda.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+/*
+    sell token for plain sol by investor, the payout is unwrapped through a temporary wsol account
+    that is closed back to the investor in the same instruction
+    @param
+    amount_in: token amount to sell
+    minimum_amount_out: least quote the investor has to receive after fees
+    close_if_empty: close the investor's token account when this sell empties it, returning the rent
+    returns the amounts actually swapped as return data
+*/
+pub fn swap_to_sol_native_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapToSolNative<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    close_if_empty: bool
+) -> Result<SwapResult> {
+    require!(
+        ctx.accounts.app_stats.pause_mode.can_sell() && ctx.accounts.pair.pause_mode.can_sell(),
+        SwapError::TradingPaused
+    );
+    let result: SwapResult = compute_sell(
+        &ctx.accounts.pair,
+        &ctx.accounts.app_stats,
+        &ctx.accounts.mint,
+        None,
+        amount_in
+    )?;
+    require!(result.amount_out >= minimum_amount_out, SwapError::ExceededSlippage);
+    ctx.accounts.pair.record_sell(result.curve_tokens, result.curve_quote)?;
+    if ctx.accounts.token_program_mint.key() == ID {
+        check_transfer_hook(&ctx.accounts.mint, &ctx.accounts.app_stats)?;
+        ctx.accounts.transfer_token_checked(result.amount_in, ctx.remaining_accounts, &[])?;
+    } else {
+        transfer(ctx.accounts.to_transfer_context(), result.amount_in)?;
+    }
+    ctx.accounts.token_account_for_swapper.reload()?;
+    if close_if_empty && ctx.accounts.token_account_for_swapper.amount == 0 {
+        // token-2022 won't close an account that still holds withheld transfer fees
+        if get_withheld_transfer_fees(&ctx.accounts.token_account_for_swapper)? > 0 {
+            harvest_withheld_transfer_fees(
+                &ctx.accounts.token_program_mint.to_account_info(),
+                &ctx.accounts.mint,
+                &ctx.accounts.token_account_for_swapper
+            )?;
+        }
+        close_token_account(ctx.accounts.close_swapper_account_context())?;
+    }
+    let seeds: &[&[u8]; 3] = &[
+        AUTHORITY_SEED,
+        ctx.accounts.mint.to_account_info().key.as_ref(),
+        &[ctx.accounts.pair.bump],
+    ];
+    let signer_seeds: &[&[&[u8]]; 1] = &[&seeds[..]];
+    let payout: u64 = result.curve_quote - result.trade_fee;
+    transfer(ctx.accounts.to_transfer_unwrap_context().with_signer(signer_seeds), payout)?;
+    transfer(ctx.accounts.to_transfer_fee_context().with_signer(signer_seeds), result.trade_fee)?;
+    // closing hands the payout and the rent the swapper just paid back as plain lamports
+    close_account(ctx.accounts.close_unwrap_context().with_signer(signer_seeds))?;
+    let trade = Trade {
+        side: TradeSide::Sell,
+        mint: ctx.accounts.mint.key(),
+        quote_mint: ctx.accounts.pair.quote_mint,
+        trader: ctx.accounts.swapper.key(),
+        amount_in: result.amount_in,
+        amount_out: result.amount_out,
+        treasury: ctx.accounts.app_stats.fee_account,
+        treasury_fee: result.trade_fee,
+        quote_transfer_fee: result.quote_transfer_fee,
+        token_transfer_fee: result.token_transfer_fee,
+        real_quote_reserves: ctx.accounts.pair.real_quote_reserves,
+        real_token_reserves: ctx.accounts.pair.real_token_reserves,
+        spot_price: ctx.accounts.pair.spot_price(ctx.accounts.mint.decimals)?,
+        slot: Clock::get()?.slot,
+    };
+    ctx.accounts.pair.stats.record_trade(&trade, result.curve_quote, result.curve_tokens);
+    ctx.accounts.app_stats.record_trade_fee(&trade.quote_mint, result.trade_fee);
+    ctx.accounts.observation_state
+        .load_mut()?
+        .record(Clock::get()?.unix_timestamp, trade.spot_price);
+    emit_cpi!(trade);
+    Ok(result)
+}
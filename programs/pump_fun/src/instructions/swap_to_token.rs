@@ -1,79 +1,106 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    token::{ transfer, Mint, Token, TokenAccount, Transfer },
+    associated_token::AssociatedToken,
+    token::{ transfer, Transfer },
     token_2022::{ transfer_checked, TransferChecked, ID },
     token_interface,
 };
 
-use crate::{ curve::{ to_u128, to_u64 }, error::SwapError, AppStats, SwapPair, APP_STATS_SEED, AUTHORITY_SEED, DENOMINATOR, FEE_PERCENTAGE };
+use crate::{
+    compute_buy,
+    error::SwapError,
+    utils::{ check_transfer_hook, transfer_checked_with_hook },
+    AppStats,
+    PriceObservations,
+    SwapPair,
+    SwapResult,
+    Trade,
+    TradeSide,
+    APP_STATS_SEED,
+    AUTHORITY_SEED,
+    SWAP_PAIR_SEED,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SwapToToken<'info> {
     #[account(mut)]
     pub swapper: Signer<'info>,
 
     /// CHECK:
+    #[account(address = pair.authority()? @ SwapError::IncorrectSwapAccount)]
     pub pda: AccountInfo<'info>,
 
     #[account(token::token_program = token_program_mint)]
     pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
-    pub wsol: Box<Account<'info, Mint>>,
+    #[account(address = pair.quote_mint @ SwapError::IncorrectSwapAccount)]
+    pub quote_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     // pair account
     #[account(
       mut,
-      constraint = pair.mint == mint.key(),
-      constraint = pair.token_account == token_account_for_pda.key(),
+      seeds = [SWAP_PAIR_SEED, mint.key().as_ref()],
+      bump,
+      constraint = pair.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+      constraint = !pair.graduated @ SwapError::PairGraduated,
     )]
     pub pair: Box<Account<'info, SwapPair>>,
 
-    // token account for swapper
+    // price observations of the pair
+    #[account(mut, address = pair.observation_state @ SwapError::IncorrectSwapAccount)]
+    pub observation_state: AccountLoader<'info, PriceObservations>,
+
+    // token account for swapper, created on the first buy
     #[account(
-      mut,
-      token::mint = mint,
-      token::token_program = token_program_mint,
+      init_if_needed,
+      payer = swapper,
+      associated_token::mint = mint,
+      associated_token::authority = swapper,
+      associated_token::token_program = token_program_mint,
     )]
     pub token_account_for_swapper: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    // wsol account for swapper
+    // quote account for swapper
     #[account(
       mut,
-      constraint = native_account_for_swapper.is_native() == true,
+      token::mint = quote_mint,
+      token::token_program = token_program_quote,
     )]
-    pub native_account_for_swapper: Box<Account<'info, TokenAccount>>,
+    pub quote_account_for_swapper: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     // token account for pda
     #[account(
       mut,
+      address = pair.token_account @ SwapError::IncorrectSwapAccount,
       token::token_program = token_program_mint,
-      token::authority = pda,
     )]
     pub token_account_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    // wsol account for pda
-    #[account(
-      mut,
-      token::authority = pda,
-      constraint = native_account_for_pda.is_native() == true,
-    )]
-    pub native_account_for_pda: Box<Account<'info, TokenAccount>>,
+    // quote account for pda
+    #[account(mut, address = pair.quote_account @ SwapError::IncorrectSwapAccount)]
+    pub quote_account_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-    // fee account for fee lamports
+    // treasury quote account, owned by the app state fee wallet
     #[account(
-        mut, 
-        constraint = app_stats.fee_account == fee_account.key()
+        mut,
+        token::mint = quote_mint,
+        token::authority = app_stats.fee_account,
+        token::token_program = token_program_quote,
     )]
-    pub fee_account: Box<Account<'info, TokenAccount>>,
+    pub fee_quote_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     // app state account
     #[account(
-        seeds = [APP_STATS_SEED],
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
         bump
     )]
     pub app_stats: Box<Account<'info, AppStats>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program_quote: Interface<'info, token_interface::TokenInterface>,
     pub token_program_mint: Interface<'info, token_interface::TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 impl<'info> SwapToToken<'info> {
@@ -86,52 +113,74 @@ impl<'info> SwapToToken<'info> {
         CpiContext::new(self.token_program_mint.to_account_info(), cpi_accounts)
     }
 
-    pub fn transfer_checked_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
-        let cpi_program: AccountInfo = self.token_program_mint.to_account_info();
-        let cpi_accounts: TransferChecked = TransferChecked {
-            from: self.token_account_for_pda.to_account_info(),
-            to: self.token_account_for_swapper.to_account_info(),
-            authority: self.pda.to_account_info(),
-            mint: self.mint.to_account_info(),
-        };
-
-        CpiContext::new(cpi_program, cpi_accounts)
+    fn transfer_token_checked(
+        &self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]]
+    ) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program_mint.to_account_info(),
+            self.token_account_for_pda.to_account_info(),
+            &self.mint,
+            self.token_account_for_swapper.to_account_info(),
+            self.pda.to_account_info(),
+            hook_accounts,
+            amount,
+            signer_seeds
+        )
     }
 
-    fn to_transfer_native_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts: Transfer = Transfer {
-            from: self.native_account_for_swapper.to_account_info().clone(),
-            to: self.native_account_for_pda.to_account_info().clone(),
-            authority: self.swapper.to_account_info().clone(),
+    fn to_transfer_quote_context(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts: TransferChecked = TransferChecked {
+            from: self.quote_account_for_swapper.to_account_info(),
+            to: self.quote_account_for_pda.to_account_info(),
+            authority: self.swapper.to_account_info(),
+            mint: self.quote_mint.to_account_info(),
         };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+        CpiContext::new(self.token_program_quote.to_account_info(), cpi_accounts)
     }
 
-    fn to_transfer_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts: Transfer = Transfer {
-            from: self.native_account_for_swapper.to_account_info().clone(),
-            to: self.fee_account.to_account_info().clone(),
-            authority: self.swapper.to_account_info().clone(),
+    fn to_transfer_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts: TransferChecked = TransferChecked {
+            from: self.quote_account_for_swapper.to_account_info(),
+            to: self.fee_quote_account.to_account_info(),
+            authority: self.swapper.to_account_info(),
+            mint: self.quote_mint.to_account_info(),
         };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+        CpiContext::new(self.token_program_quote.to_account_info(), cpi_accounts)
     }
 }
 
 /*
-    buy token with sol by investor
-    @param: amount_in : sol amount to buy token
+    buy token with the pair's quote by investor
+    transfer fees of token-2022 mints are taken out before the curve sees an amount
+    @param: amount_in : quote amount to buy token, including fee
+    @param: minimum_amount_out : least tokens the investor has to receive after transfer fees
+    returns the amounts actually swapped as return data
 */
 
-pub fn swap_to_token_handler(ctx: Context<SwapToToken>, amount_in: u64) -> Result<()> {
-    let fee_amount: u64 = amount_in / DENOMINATOR as u64 * FEE_PERCENTAGE as u64;
-    let source_amount: u128 = to_u128(amount_in - fee_amount)?;
-    let swap_source_amount: u128 = to_u128(ctx.accounts.native_account_for_pda.amount)?;
-    let swap_destination_amount: u128 = to_u128(ctx.accounts.token_account_for_pda.amount)?;
-    let (source_amount, destination_amount) = ctx.accounts.pair.curve
-        .swap_a_to_b(source_amount, swap_source_amount, swap_destination_amount)
-        .ok_or(SwapError::ZeroTradingTokens)?;
-    transfer(ctx.accounts.to_transfer_native_context(), to_u64(source_amount)?)?;
-    transfer(ctx.accounts.to_transfer_fee_context(), fee_amount)?;
+pub fn swap_to_token_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapToToken<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64
+) -> Result<SwapResult> {
+    require!(
+        ctx.accounts.app_stats.pause_mode.can_buy() && ctx.accounts.pair.pause_mode.can_buy(),
+        SwapError::TradingPaused
+    );
+    let result: SwapResult = compute_buy(
+        &ctx.accounts.pair,
+        &ctx.accounts.app_stats,
+        &ctx.accounts.mint,
+        Some(&ctx.accounts.quote_mint),
+        amount_in
+    )?;
+    require!(result.amount_out >= minimum_amount_out, SwapError::ExceededSlippage);
+    ctx.accounts.pair.record_buy(result.curve_quote, result.curve_tokens)?;
+    let quote_decimals: u8 = ctx.accounts.quote_mint.decimals;
+    transfer_checked(ctx.accounts.to_transfer_quote_context(), result.amount_in - result.trade_fee, quote_decimals)?;
+    transfer_checked(ctx.accounts.to_transfer_fee_context(), result.trade_fee, quote_decimals)?;
     let seeds: &[&[u8]; 3] = &[
         AUTHORITY_SEED,
         ctx.accounts.mint.to_account_info().key.as_ref(),
@@ -139,16 +188,35 @@ pub fn swap_to_token_handler(ctx: Context<SwapToToken>, amount_in: u64) -> Resul
     ];
     let signer_seeds: &[&[&[u8]]; 1] = &[&seeds[..]];
     if ctx.accounts.token_program_mint.key() == ID {
-        transfer_checked(
-            ctx.accounts.transfer_checked_ctx().with_signer(signer_seeds),
-            to_u64(destination_amount)?,
-            ctx.accounts.mint.decimals
-        )?;
+        check_transfer_hook(&ctx.accounts.mint, &ctx.accounts.app_stats)?;
+        ctx.accounts.transfer_token_checked(result.curve_tokens, ctx.remaining_accounts, signer_seeds)?;
     } else {
         transfer(
             ctx.accounts.to_transfer_context().with_signer(signer_seeds),
-            to_u64(destination_amount)?
+            result.curve_tokens
         )?;
     }
-    Ok(())
+    let trade = Trade {
+        side: TradeSide::Buy,
+        mint: ctx.accounts.mint.key(),
+        quote_mint: ctx.accounts.pair.quote_mint,
+        trader: ctx.accounts.swapper.key(),
+        amount_in: result.amount_in,
+        amount_out: result.amount_out,
+        treasury: ctx.accounts.app_stats.fee_account,
+        treasury_fee: result.trade_fee,
+        quote_transfer_fee: result.quote_transfer_fee,
+        token_transfer_fee: result.token_transfer_fee,
+        real_quote_reserves: ctx.accounts.pair.real_quote_reserves,
+        real_token_reserves: ctx.accounts.pair.real_token_reserves,
+        spot_price: ctx.accounts.pair.spot_price(ctx.accounts.mint.decimals)?,
+        slot: Clock::get()?.slot,
+    };
+    ctx.accounts.pair.stats.record_trade(&trade, result.curve_quote, result.curve_tokens);
+    ctx.accounts.app_stats.record_trade_fee(&trade.quote_mint, result.trade_fee);
+    ctx.accounts.observation_state
+        .load_mut()?
+        .record(Clock::get()?.unix_timestamp, trade.spot_price);
+    emit_cpi!(trade);
+    Ok(result)
 }
@@ -0,0 +1,206 @@
+use anchor_lang::{ prelude::*, solana_program::{ program::invoke, system_instruction } };
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{ spl_token::native_mint, sync_native, transfer, SyncNative, Token, TokenAccount, Transfer },
+    token_2022::ID,
+    token_interface,
+};
+
+use crate::{
+    compute_buy,
+    error::SwapError,
+    utils::{ check_transfer_hook, transfer_checked_with_hook },
+    AppStats,
+    PriceObservations,
+    SwapPair,
+    SwapResult,
+    Trade,
+    TradeSide,
+    APP_STATS_SEED,
+    AUTHORITY_SEED,
+    SWAP_PAIR_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapToTokenNative<'info> {
+    #[account(mut)]
+    pub swapper: Signer<'info>,
+
+    /// CHECK:
+    #[account(address = pair.authority()? @ SwapError::IncorrectSwapAccount)]
+    pub pda: AccountInfo<'info>,
+
+    #[account(token::token_program = token_program_mint)]
+    pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    // pair account
+    #[account(
+      mut,
+      seeds = [SWAP_PAIR_SEED, mint.key().as_ref()],
+      bump,
+      constraint = pair.app_stats == app_stats.key() @ SwapError::NamespaceMismatch,
+      constraint = !pair.graduated @ SwapError::PairGraduated,
+      constraint = pair.quote_mint == native_mint::ID @ SwapError::NativeQuoteRequired,
+    )]
+    pub pair: Box<Account<'info, SwapPair>>,
+
+    // price observations of the pair
+    #[account(mut, address = pair.observation_state @ SwapError::IncorrectSwapAccount)]
+    pub observation_state: AccountLoader<'info, PriceObservations>,
+
+    // token account for swapper, created on the first buy
+    #[account(
+      init_if_needed,
+      payer = swapper,
+      associated_token::mint = mint,
+      associated_token::authority = swapper,
+      associated_token::token_program = token_program_mint,
+    )]
+    pub token_account_for_swapper: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // token account for pda
+    #[account(
+      mut,
+      address = pair.token_account @ SwapError::IncorrectSwapAccount,
+      token::token_program = token_program_mint,
+    )]
+    pub token_account_for_pda: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    // wsol quote account for pda, the lamports are wrapped into it
+    #[account(mut, address = pair.quote_account @ SwapError::IncorrectSwapAccount)]
+    pub quote_account_for_pda: Box<Account<'info, TokenAccount>>,
+
+    // treasury wsol account, owned by the app state fee wallet
+    #[account(
+        mut,
+        token::authority = app_stats.fee_account,
+        constraint = fee_quote_account.is_native() @ SwapError::IncorrectFeeAccount,
+    )]
+    pub fee_quote_account: Box<Account<'info, TokenAccount>>,
+
+    // app state account
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_program_mint: Interface<'info, token_interface::TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapToTokenNative<'info> {
+    fn to_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts: Transfer = Transfer {
+            from: self.token_account_for_pda.to_account_info(),
+            to: self.token_account_for_swapper.to_account_info(),
+            authority: self.pda.to_account_info(),
+        };
+        CpiContext::new(self.token_program_mint.to_account_info(), cpi_accounts)
+    }
+
+    fn transfer_token_checked(
+        &self,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]]
+    ) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program_mint.to_account_info(),
+            self.token_account_for_pda.to_account_info(),
+            &self.mint,
+            self.token_account_for_swapper.to_account_info(),
+            self.pda.to_account_info(),
+            hook_accounts,
+            amount,
+            signer_seeds
+        )
+    }
+
+    /// Moves lamports from the swapper into a wsol account and syncs its token amount
+    fn wrap_lamports(&self, to: &Account<'info, TokenAccount>, lamports: u64) -> Result<()> {
+        invoke(
+            &system_instruction::transfer(self.swapper.key, &to.key(), lamports),
+            &[
+                self.swapper.to_account_info(),
+                to.to_account_info(),
+                self.system_program.to_account_info(),
+            ]
+        )?;
+        sync_native(
+            CpiContext::new(self.token_program.to_account_info(), SyncNative {
+                account: to.to_account_info(),
+            })
+        )
+    }
+}
+
+/*
+    buy token with plain sol by investor, the sol is wrapped into the pair's wsol vault
+    @param: amount_in : lamports to buy token, including fee
+    @param: minimum_amount_out : least tokens the investor has to receive after transfer fees
+    returns the amounts actually swapped as return data
+*/
+pub fn swap_to_token_native_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapToTokenNative<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64
+) -> Result<SwapResult> {
+    require!(
+        ctx.accounts.app_stats.pause_mode.can_buy() && ctx.accounts.pair.pause_mode.can_buy(),
+        SwapError::TradingPaused
+    );
+    let result: SwapResult = compute_buy(
+        &ctx.accounts.pair,
+        &ctx.accounts.app_stats,
+        &ctx.accounts.mint,
+        None,
+        amount_in
+    )?;
+    require!(result.amount_out >= minimum_amount_out, SwapError::ExceededSlippage);
+    ctx.accounts.pair.record_buy(result.curve_quote, result.curve_tokens)?;
+    ctx.accounts.wrap_lamports(&ctx.accounts.quote_account_for_pda, result.curve_quote)?;
+    ctx.accounts.wrap_lamports(&ctx.accounts.fee_quote_account, result.trade_fee)?;
+    let seeds: &[&[u8]; 3] = &[
+        AUTHORITY_SEED,
+        ctx.accounts.mint.to_account_info().key.as_ref(),
+        &[ctx.accounts.pair.bump],
+    ];
+    let signer_seeds: &[&[&[u8]]; 1] = &[&seeds[..]];
+    if ctx.accounts.token_program_mint.key() == ID {
+        check_transfer_hook(&ctx.accounts.mint, &ctx.accounts.app_stats)?;
+        ctx.accounts.transfer_token_checked(result.curve_tokens, ctx.remaining_accounts, signer_seeds)?;
+    } else {
+        transfer(
+            ctx.accounts.to_transfer_context().with_signer(signer_seeds),
+            result.curve_tokens
+        )?;
+    }
+    let trade = Trade {
+        side: TradeSide::Buy,
+        mint: ctx.accounts.mint.key(),
+        quote_mint: ctx.accounts.pair.quote_mint,
+        trader: ctx.accounts.swapper.key(),
+        amount_in: result.amount_in,
+        amount_out: result.amount_out,
+        treasury: ctx.accounts.app_stats.fee_account,
+        treasury_fee: result.trade_fee,
+        quote_transfer_fee: result.quote_transfer_fee,
+        token_transfer_fee: result.token_transfer_fee,
+        real_quote_reserves: ctx.accounts.pair.real_quote_reserves,
+        real_token_reserves: ctx.accounts.pair.real_token_reserves,
+        spot_price: ctx.accounts.pair.spot_price(ctx.accounts.mint.decimals)?,
+        slot: Clock::get()?.slot,
+    };
+    ctx.accounts.pair.stats.record_trade(&trade, result.curve_quote, result.curve_tokens);
+    ctx.accounts.app_stats.record_trade_fee(&trade.quote_mint, result.trade_fee);
+    ctx.accounts.observation_state
+        .load_mut()?
+        .record(Clock::get()?.unix_timestamp, trade.spot_price);
+    emit_cpi!(trade);
+    Ok(result)
+}
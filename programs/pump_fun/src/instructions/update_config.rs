@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    authorize_admin,
+    AdminAction,
+    AppStats,
+    ConfigParams,
+    ConfigUpdated,
+    Governance,
+    Proposal,
+    APP_STATS_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    // app owner, or anyone executing an approved proposal once governance is enabled
+    pub authority: Signer<'info>,
+
+    // app state
+    #[account(
+        mut,
+        seeds = [APP_STATS_SEED, app_stats.namespace.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub app_stats: Box<Account<'info, AppStats>>,
+
+    // governance account, required once governance is enabled
+    #[account(address = app_stats.governance)]
+    pub governance: Option<Box<Account<'info, Governance>>>,
+
+    // approved proposal for this exact update
+    #[account(mut)]
+    pub proposal: Option<Box<Account<'info, Proposal>>>,
+}
+
+/*
+    @dev update app config by owner or governance.
+    @param params: new config values, unset fields are left unchanged
+*/
+pub fn update_config_handler(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+    params.validate()?;
+    authorize_admin(
+        &ctx.accounts.app_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.governance,
+        &mut ctx.accounts.proposal,
+        &AdminAction::UpdateConfig(params.clone())
+    )?;
+    let app_stats: &mut Box<Account<AppStats>> = &mut ctx.accounts.app_stats;
+    if let Some(fee_lamports) = params.fee_lamports {
+        app_stats.fee_lamports = fee_lamports;
+    }
+    if let Some(fee_account) = params.fee_account {
+        app_stats.fee_account = fee_account;
+    }
+    if let Some(fee_basis_points) = params.fee_basis_points {
+        app_stats.fee_basis_points = fee_basis_points;
+    }
+    if let Some(graduation_threshold) = params.graduation_threshold {
+        app_stats.graduation_threshold = graduation_threshold;
+    }
+    emit_cpi!(ConfigUpdated {
+        app_stats: app_stats.key(),
+        authority: ctx.accounts.authority.key(),
+        fee_lamports: app_stats.fee_lamports,
+        fee_account: app_stats.fee_account,
+        fee_basis_points: app_stats.fee_basis_points,
+        graduation_threshold: app_stats.graduation_threshold,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
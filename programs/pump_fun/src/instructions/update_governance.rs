@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    AdminAction,
+    Governance,
+    GovernanceParams,
+    GovernanceUpdated,
+    Proposal,
+    GOVERNANCE_SEED,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateGovernance<'info> {
+    pub authority: Signer<'info>,
+
+    // governance account
+    #[account(
+        mut,
+        seeds = [GOVERNANCE_SEED, governance.app_stats.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Box<Account<'info, Governance>>,
+
+    // approved proposal for this exact signer set
+    #[account(mut, has_one = governance)]
+    pub proposal: Box<Account<'info, Proposal>>,
+}
+
+/*
+    @dev replace the governance signer set, threshold and delay through an approved proposal.
+    pending proposals created under the old config can no longer be executed
+    @param params: new signer set, approval threshold and timelock delay
+*/
+pub fn update_governance_handler(
+    ctx: Context<UpdateGovernance>,
+    params: GovernanceParams
+) -> Result<()> {
+    let action = AdminAction::UpdateGovernance(params.clone());
+    ctx.accounts.proposal.execute(&ctx.accounts.governance, &action)?;
+    ctx.accounts.governance.apply(&params)?;
+    emit_cpi!(GovernanceUpdated {
+        app_stats: ctx.accounts.governance.app_stats,
+        governance: ctx.accounts.governance.key(),
+        params,
+        config_version: ctx.accounts.governance.config_version,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
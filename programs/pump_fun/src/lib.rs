@@ -1,13 +1,16 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod dfs_precise_number;
 pub mod curve;
+pub mod utils;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -17,15 +20,15 @@ declare_id!("79UwNMhNjXyKXAANRjjVnndagJPtEhadWseBpFuMrboF");
 pub mod pump_fun {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, fee_lamports: u64) -> Result<()> {
-        initialize::handler(ctx, fee_lamports)
+    pub fn initialize(ctx: Context<Initialize>, namespace: u64, fee_lamports: u64) -> Result<()> {
+        initialize::handler(ctx, namespace, fee_lamports)
     }
     pub fn create_token(ctx: Context<CreateToken>, supply: u64, bump: u8) -> Result<()> {
         create_token_handler(ctx, supply, bump)
     }
 
-    pub fn initialize_linear_price(
-        ctx: Context<InitializeLinearPrice>,
+    pub fn initialize_linear_price<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeLinearPrice<'info>>,
         slope_numerator: u64,
         slope_denominator: u64,
         initial_token_a_price_numerator: u64,
@@ -42,23 +45,155 @@ pub mod pump_fun {
         )
     }
 
-    pub fn swap_to_token(ctx: Context<SwapToToken>, amount_in: u64) -> Result<()> {
-        swap_to_token_handler(ctx, amount_in)
+    pub fn initialize_linear_price_with_preset<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeLinearPriceWithPreset<'info>>,
+        preset_id: u64,
+        bump: u8
+    ) -> Result<()> {
+        initialize_linear_price_with_preset_handler(ctx, preset_id, bump)
     }
 
-    pub fn swap_to_sol(ctx: Context<SwapToSol>, amount_in: u64) -> Result<()> {
-        swap_to_sol_handler(ctx, amount_in)
+    pub fn swap_to_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapToToken<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64
+    ) -> Result<SwapResult> {
+        swap_to_token_handler(ctx, amount_in, minimum_amount_out)
     }
 
-    pub fn proxy_initialize(
-        ctx: Context<ProxyInitialize>,
-        init_amount_1: u64,
-        open_time: u64
-    ) -> Result<()> {
-        proxy_initialize_handler(ctx, init_amount_1, open_time)
+    pub fn swap_to_sol<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapToSol<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        close_if_empty: bool
+    ) -> Result<SwapResult> {
+        swap_to_sol_handler(ctx, amount_in, minimum_amount_out, close_if_empty)
+    }
+
+    pub fn swap_to_token_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapToTokenNative<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64
+    ) -> Result<SwapResult> {
+        swap_to_token_native_handler(ctx, amount_in, minimum_amount_out)
+    }
+
+    pub fn swap_to_sol_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapToSolNative<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        close_if_empty: bool
+    ) -> Result<SwapResult> {
+        swap_to_sol_native_handler(ctx, amount_in, minimum_amount_out, close_if_empty)
+    }
+
+    pub fn skim<'info>(ctx: Context<'_, '_, '_, 'info, Skim<'info>>) -> Result<()> {
+        skim_handler(ctx)
+    }
+
+    pub fn quote_buy(ctx: Context<Quote>, amount_in: u64) -> Result<SwapResult> {
+        quote_buy_handler(ctx, amount_in)
+    }
+
+    pub fn quote_sell(ctx: Context<Quote>, amount_in: u64) -> Result<SwapResult> {
+        quote_sell_handler(ctx, amount_in)
+    }
+
+    pub fn get_spot_price(ctx: Context<Quote>) -> Result<u64> {
+        get_spot_price_handler(ctx)
+    }
+
+    pub fn get_market_cap(ctx: Context<Quote>) -> Result<u64> {
+        get_market_cap_handler(ctx)
+    }
+
+    pub fn get_circulating_market_cap(ctx: Context<Quote>) -> Result<u64> {
+        get_circulating_market_cap_handler(ctx)
+    }
+
+    pub fn get_buyout_cost(ctx: Context<Quote>) -> Result<u64> {
+        get_buyout_cost_handler(ctx)
+    }
+
+    pub fn get_depth_table(ctx: Context<Quote>, step_bps: u16) -> Result<Vec<curve::DepthLevel>> {
+        get_depth_table_handler(ctx, step_bps)
+    }
+
+    pub fn proxy_initialize(ctx: Context<ProxyInitialize>, open_time: u64) -> Result<()> {
+        proxy_initialize_handler(ctx, open_time)
     }
 
     pub fn create_account(ctx: Context<CreateAccount>) -> Result<()> {
         create_account_handler(ctx)
     }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        update_config_handler(ctx, params)
+    }
+
+    pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Pubkey) -> Result<()> {
+        propose_owner_handler(ctx, new_owner)
+    }
+
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        accept_owner_handler(ctx)
+    }
+
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        params: GovernanceParams
+    ) -> Result<()> {
+        initialize_governance_handler(ctx, params)
+    }
+
+    pub fn update_governance(
+        ctx: Context<UpdateGovernance>,
+        params: GovernanceParams
+    ) -> Result<()> {
+        update_governance_handler(ctx, params)
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: AdminAction) -> Result<()> {
+        create_proposal_handler(ctx, action)
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        approve_proposal_handler(ctx)
+    }
+
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        cancel_proposal_handler(ctx)
+    }
+
+    pub fn set_pause(ctx: Context<SetPause>, mode: PauseMode) -> Result<()> {
+        set_pause_handler(ctx, mode)
+    }
+
+    pub fn set_pair_pause(ctx: Context<SetPairPause>, mode: PauseMode) -> Result<()> {
+        set_pair_pause_handler(ctx, mode)
+    }
+
+    pub fn set_curve_preset(
+        ctx: Context<SetCurvePreset>,
+        preset_id: u64,
+        params: CurvePresetParams
+    ) -> Result<()> {
+        set_curve_preset_handler(ctx, preset_id, params)
+    }
+
+    pub fn set_curve_bounds(ctx: Context<SetCurveBounds>, bounds: CurveBounds) -> Result<()> {
+        set_curve_bounds_handler(ctx, bounds)
+    }
+
+    pub fn set_quote_mint(ctx: Context<SetQuoteMint>, allowed: bool) -> Result<()> {
+        set_quote_mint_handler(ctx, allowed)
+    }
+
+    pub fn set_hook_program(ctx: Context<SetHookProgram>, allowed: bool) -> Result<()> {
+        set_hook_program_handler(ctx, allowed)
+    }
+
+    pub fn set_supply_policy(ctx: Context<SetSupplyPolicy>, policy: SupplyPolicy) -> Result<()> {
+        set_supply_policy_handler(ctx, policy)
+    }
 }
@@ -1,8 +1,150 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::native_mint;
+
+use crate::{
+  error::SwapError,
+  CurveBounds,
+  PauseMode,
+  SupplyPolicy,
+  DENOMINATOR,
+  MAX_HOOK_PROGRAMS,
+  MAX_QUOTE_MINTS,
+};
 
 #[account]
 pub struct AppStats {
+  /// launchpad id, several independent AppStats can share one deployment
+  pub namespace: u64,
   pub owner: Pubkey,
   pub fee_lamports: u64,
+  /// treasury wallet, creation fees are paid to it and trade fees to its quote token accounts
   pub fee_account: Pubkey,
-}
\ No newline at end of file
+  /// owner proposed by `propose_owner`, default pubkey when no transfer is pending
+  pub pending_owner: Pubkey,
+  /// trade fee taken on every swap, in basis points of DENOMINATOR
+  pub fee_basis_points: u16,
+  /// quote amount a pair needs to hold before it can graduate to raydium,
+  /// in CURVE_QUOTE_DECIMALS units and rescaled to the quote mint decimals per pair
+  pub graduation_threshold: u64,
+  /// governance account, once set every owner-gated action needs an approved proposal
+  pub governance: Pubkey,
+  /// global circuit breaker
+  pub pause_mode: PauseMode,
+  /// limits for launches with custom curve parameters instead of a preset
+  pub curve_bounds: CurveBounds,
+  /// mints pairs can be quoted in, the first `quote_mint_count` entries are used
+  pub quote_mints: [Pubkey; MAX_QUOTE_MINTS],
+  pub quote_mint_count: u8,
+  /// token-2022 transfer hook programs launched mints may use, the first `hook_program_count` are used
+  pub hook_programs: [Pubkey; MAX_HOOK_PROGRAMS],
+  pub hook_program_count: u8,
+  /// supply cap, decimals range and graduation reserve for new launches
+  pub supply_policy: SupplyPolicy,
+  /// tokens created under this app state
+  pub tokens_launched: u64,
+  /// pairs migrated to raydium
+  pub pairs_graduated: u64,
+  /// creation fees plus trade fees of wsol quoted pairs, in lamports.
+  /// fees in other quote mints are only tracked per pair
+  pub total_protocol_fees: u64,
+}
+
+impl AppStats {
+  /// Adds a trade fee paid in `quote_mint` to the protocol total
+  pub fn record_trade_fee(&mut self, quote_mint: &Pubkey, fee: u64) {
+    if *quote_mint == native_mint::ID {
+      self.total_protocol_fees = self.total_protocol_fees.saturating_add(fee);
+    }
+  }
+
+  /// Calculate the trade fee for a swap amount
+  pub fn trade_fee(&self, amount: u64) -> Result<u64> {
+    let fee = (amount as u128)
+      .checked_mul(self.fee_basis_points as u128)
+      .and_then(|v| v.checked_div(DENOMINATOR as u128))
+      .ok_or(SwapError::FeeCalculationFailure)?;
+    u64::try_from(fee).map_err(|_| SwapError::ConversionFailure.into())
+  }
+
+  /// Calculate the trade fee charged on top of `net_amount`, so the fee is the same share
+  /// of `net_amount` plus the fee that `trade_fee` takes of a swap amount
+  pub fn trade_fee_on_top(&self, net_amount: u64) -> Result<u64> {
+    let remainder_bps = DENOMINATOR.checked_sub(self.fee_basis_points).ok_or(SwapError::FeeCalculationFailure)?;
+    let fee = (net_amount as u128)
+      .checked_mul(self.fee_basis_points as u128)
+      .and_then(|v| v.checked_div(remainder_bps as u128))
+      .ok_or(SwapError::FeeCalculationFailure)?;
+    u64::try_from(fee).map_err(|_| SwapError::ConversionFailure.into())
+  }
+
+  pub fn is_quote_mint_allowed(&self, mint: &Pubkey) -> bool {
+    self.quote_mints[..self.quote_mint_count as usize].contains(mint)
+  }
+
+  /// Adds `mint` to or removes it from the quote allowlist, pairs already quoted in a removed
+  /// mint keep trading
+  pub fn set_quote_mint(&mut self, mint: Pubkey, allowed: bool) -> Result<()> {
+    let count = self.quote_mint_count as usize;
+    let index = self.quote_mints[..count].iter().position(|m| *m == mint);
+    match (index, allowed) {
+      (None, true) => {
+        require!(count < MAX_QUOTE_MINTS, SwapError::QuoteAllowlistFull);
+        self.quote_mints[count] = mint;
+        self.quote_mint_count += 1;
+      }
+      (Some(index), false) => {
+        self.quote_mints[index] = self.quote_mints[count - 1];
+        self.quote_mints[count - 1] = Pubkey::default();
+        self.quote_mint_count -= 1;
+      }
+      _ => {}
+    }
+    Ok(())
+  }
+
+  pub fn is_hook_program_allowed(&self, program: &Pubkey) -> bool {
+    self.hook_programs[..self.hook_program_count as usize].contains(program)
+  }
+
+  /// Adds `program` to or removes it from the transfer hook allowlist, swaps of mints using a
+  /// removed hook fail until it is allowed again
+  pub fn set_hook_program(&mut self, program: Pubkey, allowed: bool) -> Result<()> {
+    let count = self.hook_program_count as usize;
+    let index = self.hook_programs[..count].iter().position(|p| *p == program);
+    match (index, allowed) {
+      (None, true) => {
+        require!(count < MAX_HOOK_PROGRAMS, SwapError::HookAllowlistFull);
+        self.hook_programs[count] = program;
+        self.hook_program_count += 1;
+      }
+      (Some(index), false) => {
+        self.hook_programs[index] = self.hook_programs[count - 1];
+        self.hook_programs[count - 1] = Pubkey::default();
+        self.hook_program_count -= 1;
+      }
+      _ => {}
+    }
+    Ok(())
+  }
+}
+
+/// Config values that can be changed by the owner, `None` leaves the current value in place
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ConfigParams {
+  pub fee_lamports: Option<u64>,
+  pub fee_account: Option<Pubkey>,
+  pub fee_basis_points: Option<u16>,
+  pub graduation_threshold: Option<u64>,
+}
+
+impl ConfigParams {
+  pub fn validate(&self) -> Result<()> {
+    if let Some(fee_basis_points) = self.fee_basis_points {
+      require!(fee_basis_points < DENOMINATOR, SwapError::InvalidFee);
+    }
+    if let Some(fee_account) = self.fee_account {
+      require!(fee_account != Pubkey::default(), SwapError::IncorrectFeeAccount);
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::{ curve::LinearPriceCurve, error::SwapError };
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveType {
+  Linear,
+}
+
+/// Curve published by the owner that creators can launch with by id
+#[account]
+pub struct CurvePreset {
+  pub app_stats: Pubkey,
+  pub id: u64,
+  pub params: CurvePresetParams,
+  pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct CurvePresetParams {
+  /// display name, utf8 padded with zeros
+  pub name: [u8; 32],
+  pub curve_type: CurveType,
+  pub curve: LinearPriceCurve,
+  /// token supply the pair has to be launched with
+  pub supply: u64,
+  /// quote amount the pair needs to hold before it can graduate, in CURVE_QUOTE_DECIMALS units
+  pub graduation_threshold: u64,
+  /// disabled presets stay readable but cannot be launched with
+  pub enabled: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Fraction {
+  pub numerator: u64,
+  pub denominator: u64,
+}
+
+impl Fraction {
+  /// Compares numerator / denominator against another fraction without dividing
+  fn le(&self, numerator: u64, denominator: u64) -> bool {
+    (self.numerator as u128) * (denominator as u128) <= (numerator as u128) * (self.denominator as u128)
+  }
+
+  fn ge(&self, numerator: u64, denominator: u64) -> bool {
+    (self.numerator as u128) * (denominator as u128) >= (numerator as u128) * (self.denominator as u128)
+  }
+}
+
+/// Limits on the raw curve parameters creators may launch with instead of a preset
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct CurveBounds {
+  pub allow_custom: bool,
+  pub min_slope: Fraction,
+  pub max_slope: Fraction,
+  pub min_initial_price: Fraction,
+  pub max_initial_price: Fraction,
+}
+
+impl Default for CurveBounds {
+  fn default() -> Self {
+    Self {
+      allow_custom: true,
+      min_slope: Fraction { numerator: 0, denominator: 1 },
+      max_slope: Fraction { numerator: u64::MAX, denominator: 1 },
+      min_initial_price: Fraction { numerator: 0, denominator: 1 },
+      max_initial_price: Fraction { numerator: u64::MAX, denominator: 1 },
+    }
+  }
+}
+
+impl CurveBounds {
+  pub fn validate(&self) -> Result<()> {
+    require!(
+      self.min_slope.denominator != 0
+        && self.max_slope.denominator != 0
+        && self.min_initial_price.denominator != 0
+        && self.max_initial_price.denominator != 0,
+      SwapError::InvalidCurve
+    );
+    Ok(())
+  }
+
+  /// Checks custom launch parameters against the owner's bounds, the curve itself is
+  /// validated separately by LinearPriceCurve::validate
+  pub fn check(&self, curve: &LinearPriceCurve) -> Result<()> {
+    require!(self.allow_custom, SwapError::UnsupportedCurveType);
+    let slope = (curve.slope_numerator, curve.slope_denominator);
+    let price = (curve.initial_token_a_price_numerator, curve.initial_token_a_price_denominator);
+    require!(
+      self.min_slope.le(slope.0, slope.1)
+        && self.max_slope.ge(slope.0, slope.1)
+        && self.min_initial_price.le(price.0, price.1)
+        && self.max_initial_price.ge(price.0, price.1),
+      SwapError::CurveOutOfBounds
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+  error::SwapError,
+  AppStats,
+  ConfigParams,
+  CurveBounds,
+  CurvePresetParams,
+  PauseMode,
+  SupplyPolicy,
+  MAX_GOVERNANCE_SIGNERS,
+};
+
+#[account]
+pub struct Governance {
+  pub app_stats: Pubkey,
+  pub signers: [Pubkey; MAX_GOVERNANCE_SIGNERS],
+  pub signer_count: u8,
+  /// approvals required before a proposal can be executed
+  pub threshold: u8,
+  /// seconds between a proposal reaching the threshold and becoming executable
+  pub timelock_delay: i64,
+  /// bumped whenever the signer set changes, invalidating pending proposals
+  pub config_version: u32,
+  pub proposal_count: u64,
+  pub bump: u8,
+}
+
+impl Governance {
+  pub fn signer_index(&self, key: &Pubkey) -> Option<usize> {
+    self.signers[..self.signer_count as usize]
+      .iter()
+      .position(|signer| signer == key)
+  }
+
+  pub fn apply(&mut self, params: &GovernanceParams) -> Result<()> {
+    params.validate()?;
+    self.signers = params.signers;
+    self.signer_count = params.signer_count;
+    self.threshold = params.threshold;
+    self.timelock_delay = params.timelock_delay;
+    self.config_version = self.config_version.checked_add(1).ok_or(SwapError::CalculationFailure)?;
+    Ok(())
+  }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct GovernanceParams {
+  /// only the first `signer_count` entries are used
+  pub signers: [Pubkey; MAX_GOVERNANCE_SIGNERS],
+  pub signer_count: u8,
+  pub threshold: u8,
+  pub timelock_delay: i64,
+}
+
+impl GovernanceParams {
+  pub fn validate(&self) -> Result<()> {
+    let count = self.signer_count as usize;
+    require!(
+      count <= MAX_GOVERNANCE_SIGNERS
+        && self.threshold > 0
+        && self.threshold <= self.signer_count
+        && self.timelock_delay >= 0,
+      SwapError::InvalidGovernanceConfig
+    );
+    let signers = &self.signers[..count];
+    for (i, signer) in signers.iter().enumerate() {
+      require!(
+        *signer != Pubkey::default() && !signers[..i].contains(signer),
+        SwapError::InvalidGovernanceConfig
+      );
+    }
+    Ok(())
+  }
+}
+
+/// Every owner-gated action, proposals must match the instruction they are consumed by exactly
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum AdminAction {
+  UpdateConfig(ConfigParams),
+  ProposeOwner(Pubkey),
+  UpdateGovernance(GovernanceParams),
+  SetPause(PauseMode),
+  /// pair mint and mode
+  SetPairPause(Pubkey, PauseMode),
+  /// preset id and params
+  SetCurvePreset(u64, CurvePresetParams),
+  SetCurveBounds(CurveBounds),
+  /// quote mint and whether it is allowed
+  SetQuoteMint(Pubkey, bool),
+  /// transfer hook program and whether it is allowed
+  SetHookProgram(Pubkey, bool),
+  SetSupplyPolicy(SupplyPolicy),
+}
+
+#[account]
+pub struct Proposal {
+  pub governance: Pubkey,
+  pub id: u64,
+  pub proposer: Pubkey,
+  pub action: AdminAction,
+  /// governance config version the proposal was created under
+  pub config_version: u32,
+  /// bitmap of approving signer indexes
+  pub approvals: u16,
+  pub approval_count: u8,
+  /// when the approval threshold was reached, 0 until then
+  pub approved_at: i64,
+  pub executed: bool,
+  pub cancelled: bool,
+  pub bump: u8,
+}
+
+impl Proposal {
+  pub fn approve(&mut self, governance: &Governance, signer_index: usize) -> Result<()> {
+    require!(!self.executed && !self.cancelled, SwapError::ProposalClosed);
+    require!(self.config_version == governance.config_version, SwapError::StaleProposal);
+    let bit = 1u16 << signer_index;
+    require!(self.approvals & bit == 0, SwapError::ProposalAlreadyApproved);
+    self.approvals |= bit;
+    self.approval_count += 1;
+    if self.approval_count == governance.threshold {
+      self.approved_at = Clock::get()?.unix_timestamp;
+    }
+    Ok(())
+  }
+
+  /// Marks the proposal as executed if it is approved, past its timelock and proposes `action`
+  pub fn execute(&mut self, governance: &Account<Governance>, action: &AdminAction) -> Result<()> {
+    require_keys_eq!(self.governance, governance.key(), SwapError::ProposalActionMismatch);
+    require!(!self.executed && !self.cancelled, SwapError::ProposalClosed);
+    require!(self.config_version == governance.config_version, SwapError::StaleProposal);
+    require!(self.approval_count >= governance.threshold, SwapError::ProposalNotApproved);
+    let executable_at = self.approved_at
+      .checked_add(governance.timelock_delay)
+      .ok_or(SwapError::CalculationFailure)?;
+    require!(Clock::get()?.unix_timestamp >= executable_at, SwapError::TimelockNotElapsed);
+    require!(self.action == *action, SwapError::ProposalActionMismatch);
+    self.executed = true;
+    Ok(())
+  }
+}
+
+/// Authorizes an owner-gated action. Until governance is enabled the app owner signs directly,
+/// afterwards an approved proposal for the same action has to be passed in and is consumed
+pub fn authorize_admin<'info>(
+  app_stats: &AppStats,
+  authority: &Signer<'info>,
+  governance: &Option<Box<Account<'info, Governance>>>,
+  proposal: &mut Option<Box<Account<'info, Proposal>>>,
+  action: &AdminAction,
+) -> Result<()> {
+  if app_stats.governance == Pubkey::default() {
+    require_keys_eq!(authority.key(), app_stats.owner, SwapError::InvalidAppOwner);
+    return Ok(());
+  }
+  match (governance, proposal) {
+    (Some(governance), Some(proposal)) => proposal.execute(governance, action),
+    _ => err!(SwapError::ProposalRequired),
+  }
+}
@@ -2,9 +2,21 @@ pub mod app_stats;
 pub mod token_create;
 pub mod swap_pair;
 pub mod fees;
+pub mod governance;
+pub mod pause;
+pub mod curve_preset;
+pub mod supply_policy;
+pub mod pair_stats;
+pub mod price_observations;
 
 
 pub use app_stats::*;
 pub use token_create::*;
 pub use swap_pair::*;
-pub use fees::*;
\ No newline at end of file
+pub use fees::*;
+pub use governance::*;
+pub use pause::*;
+pub use curve_preset::*;
+pub use supply_policy::*;
+pub use pair_stats::*;
+pub use price_observations::*;
\ No newline at end of file
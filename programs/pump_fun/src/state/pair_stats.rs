@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{ Trade, TradeSide };
+
+/// Running trade statistics of a pair. Counters saturate instead of failing so a
+/// full counter can never block trading
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PairStats {
+  /// quote paid into the curve by buys, trade fees excluded
+  pub buy_volume_quote: u64,
+  /// quote paid out of the curve by sells, trade fees included
+  pub sell_volume_quote: u64,
+  pub buy_volume_tokens: u64,
+  pub sell_volume_tokens: u64,
+  pub buy_count: u64,
+  pub sell_count: u64,
+  pub last_trader: Pubkey,
+  /// spot price after the last trade, quote base units per whole token
+  pub last_price: u64,
+  pub last_trade_slot: u64,
+  /// trade fees sent to the treasury, in quote units
+  pub fees_generated: u64,
+}
+
+impl PairStats {
+  /// Adds `trade`, which moved `quote_amount` against `token_amount` on the curve
+  pub fn record_trade(&mut self, trade: &Trade, quote_amount: u64, token_amount: u64) {
+    match trade.side {
+      TradeSide::Buy => {
+        self.buy_volume_quote = self.buy_volume_quote.saturating_add(quote_amount);
+        self.buy_volume_tokens = self.buy_volume_tokens.saturating_add(token_amount);
+        self.buy_count = self.buy_count.saturating_add(1);
+      }
+      TradeSide::Sell => {
+        self.sell_volume_quote = self.sell_volume_quote.saturating_add(quote_amount);
+        self.sell_volume_tokens = self.sell_volume_tokens.saturating_add(token_amount);
+        self.sell_count = self.sell_count.saturating_add(1);
+      }
+    }
+    self.fees_generated = self.fees_generated.saturating_add(trade.treasury_fee);
+    self.last_trader = trade.trader;
+    self.last_price = trade.spot_price;
+    self.last_trade_slot = trade.slot;
+  }
+
+  pub fn trade_count(&self) -> u64 {
+    self.buy_count.saturating_add(self.sell_count)
+  }
+}
\ No newline at end of file
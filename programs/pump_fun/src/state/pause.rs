@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+/// Circuit breaker modes, set globally on AppStats and per pair on SwapPair
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PauseMode {
+  #[default]
+  Active,
+  /// no swaps and no launches
+  Halted,
+  /// only swap_to_sol, so holders can still exit
+  SellOnly,
+  /// trading continues but no new tokens or pairs can be launched
+  CreateDisabled,
+}
+
+impl PauseMode {
+  pub fn can_buy(&self) -> bool {
+    matches!(self, PauseMode::Active | PauseMode::CreateDisabled)
+  }
+
+  pub fn can_sell(&self) -> bool {
+    !matches!(self, PauseMode::Halted)
+  }
+
+  pub fn can_create(&self) -> bool {
+    matches!(self, PauseMode::Active)
+  }
+}
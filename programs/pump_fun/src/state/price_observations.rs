@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::SwapError, OBSERVATION_CAPACITY };
+
+#[zero_copy]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Observation {
+  /// sum of spot price * seconds since the pair launched, wraps on overflow
+  pub cumulative_price: u128,
+  pub timestamp: i64,
+  /// keeps the layout free of implicit padding on every target
+  pub padding: u64,
+}
+
+/// Ring buffer of cumulative price snapshots of one pair, written on every swap.
+/// Prices are the pair spot price in quote base units per whole token
+#[account(zero_copy)]
+pub struct PriceObservations {
+  pub observations: [Observation; OBSERVATION_CAPACITY],
+  pub pair: Pubkey,
+  /// spot price in effect since the newest observation
+  pub last_price: u64,
+  /// position of the newest observation
+  pub index: u16,
+  /// observations written so far, up to OBSERVATION_CAPACITY
+  pub count: u16,
+  pub padding: [u8; 4],
+}
+
+impl PriceObservations {
+  pub fn initialize(&mut self, pair: Pubkey, timestamp: i64, price: u64) {
+    self.pair = pair;
+    self.observations[0] = Observation { cumulative_price: 0, timestamp, padding: 0 };
+    self.index = 0;
+    self.count = 1;
+    self.last_price = price;
+  }
+
+  fn newest(&self) -> Observation {
+    self.observations[self.index as usize]
+  }
+
+  /// Closes the period the previous price was in effect for and switches to `price`.
+  /// Several swaps in the same second share one observation
+  pub fn record(&mut self, timestamp: i64, price: u64) {
+    let newest = self.newest();
+    if timestamp > newest.timestamp {
+      let index = (self.index as usize + 1) % OBSERVATION_CAPACITY;
+      self.observations[index] = Observation {
+        cumulative_price: accumulate(&newest, self.last_price, timestamp),
+        timestamp,
+        padding: 0,
+      };
+      self.index = index as u16;
+      self.count = (self.count + 1).min(OBSERVATION_CAPACITY as u16);
+    }
+    self.last_price = price;
+  }
+
+  /// Cumulative price at `timestamp`, interpolated between the observations around it
+  /// or extrapolated from the newest one with the current price
+  pub fn cumulative_price_at(&self, timestamp: i64) -> Result<u128> {
+    let newest = self.newest();
+    if timestamp >= newest.timestamp {
+      return Ok(accumulate(&newest, self.last_price, timestamp));
+    }
+    // walk back from the newest observation to the first one at or before `timestamp`
+    let mut after = newest;
+    for age in 1..self.count as usize {
+      let index = (self.index as usize + OBSERVATION_CAPACITY - age) % OBSERVATION_CAPACITY;
+      let before = self.observations[index];
+      if before.timestamp <= timestamp {
+        // the price was constant between two observations
+        let elapsed = (after.timestamp - before.timestamp) as u128;
+        let price = after.cumulative_price.wrapping_sub(before.cumulative_price) / elapsed;
+        return Ok(
+          before.cumulative_price.wrapping_add(price * ((timestamp - before.timestamp) as u128))
+        );
+      }
+      after = before;
+    }
+    err!(SwapError::ObservationTooOld)
+  }
+
+  /// Time-weighted average spot price over the last `window` seconds before `now`
+  pub fn twap(&self, now: i64, window: u32) -> Result<u64> {
+    require!(window > 0, SwapError::InvalidTwapWindow);
+    let start = self.cumulative_price_at(now - i64::from(window))?;
+    let end = self.cumulative_price_at(now)?;
+    let average = end.wrapping_sub(start) / u128::from(window);
+    u64::try_from(average).map_err(|_| SwapError::ConversionFailure.into())
+  }
+}
+
+fn accumulate(observation: &Observation, price: u64, timestamp: i64) -> u128 {
+  let elapsed = (timestamp - observation.timestamp) as u128;
+  observation.cumulative_price.wrapping_add(u128::from(price) * elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn observations() -> Box<PriceObservations> {
+    let mut state = Box::new(PriceObservations {
+      observations: [Observation::default(); OBSERVATION_CAPACITY],
+      pair: Pubkey::default(),
+      last_price: 0,
+      index: 0,
+      count: 0,
+      padding: [0; 4],
+    });
+    state.initialize(Pubkey::default(), 1_000, 100);
+    state
+  }
+
+  #[test]
+  fn test_twap() {
+    let mut state = observations();
+    // 100 for 10s, 200 for 30s, then 50
+    state.record(1_010, 200);
+    state.record(1_040, 50);
+    assert_eq!(state.count, 3);
+    assert_eq!(state.cumulative_price_at(1_040).unwrap(), 100 * 10 + 200 * 30);
+    assert_eq!(state.twap(1_040, 40).unwrap(), (100 * 10 + 200 * 30) / 40);
+    assert_eq!(state.twap(1_040, 20).unwrap(), 200);
+    // extrapolated with the current price
+    assert_eq!(state.twap(1_050, 10).unwrap(), 50);
+    assert_eq!(state.twap(1_050, 20).unwrap(), (200 * 10 + 50 * 10) / 20);
+    assert!(state.twap(1_040, 41).is_err());
+    assert!(state.twap(1_040, 0).is_err());
+  }
+
+  #[test]
+  fn test_same_second_swaps() {
+    let mut state = observations();
+    state.record(1_010, 500);
+    // a spike reverted inside one second never accrues any time
+    state.record(1_010, 200);
+    assert_eq!(state.count, 2);
+    state.record(1_020, 200);
+    assert_eq!(state.twap(1_020, 10).unwrap(), 200);
+  }
+
+  #[test]
+  fn test_ring_wraps() {
+    let mut state = observations();
+    for i in 1..=(OBSERVATION_CAPACITY as i64 + 10) {
+      state.record(1_000 + i, 100);
+    }
+    assert_eq!(state.count as usize, OBSERVATION_CAPACITY);
+    let now = 1_000 + OBSERVATION_CAPACITY as i64 + 10;
+    assert_eq!(state.twap(now, OBSERVATION_CAPACITY as u32 - 1).unwrap(), 100);
+    assert!(state.twap(now, OBSERVATION_CAPACITY as u32).is_err());
+  }
+
+  #[test]
+  fn test_cumulative_wraps() {
+    let mut state = observations();
+    state.observations[0].cumulative_price = u128::MAX - 50;
+    state.record(1_010, 100);
+    assert_eq!(state.twap(1_010, 10).unwrap(), 100);
+  }
+}
\ No newline at end of file
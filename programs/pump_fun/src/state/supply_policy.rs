@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{ error::SwapError, DENOMINATOR };
+
+/// Launch policy for new tokens, set by the owner on AppStats
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SupplyPolicy {
+  /// largest supply a token can be minted with, in base units
+  pub max_supply: u64,
+  pub min_decimals: u8,
+  pub max_decimals: u8,
+  /// share of the supply kept out of the curve for graduation liquidity, in basis points of DENOMINATOR
+  pub graduation_reserve_bps: u16,
+}
+
+impl Default for SupplyPolicy {
+  fn default() -> Self {
+    Self {
+      max_supply: u64::MAX,
+      min_decimals: 0,
+      max_decimals: 9,
+      graduation_reserve_bps: 0,
+    }
+  }
+}
+
+impl SupplyPolicy {
+  pub fn validate(&self) -> Result<()> {
+    require!(self.max_supply != 0, SwapError::InvalidSupply);
+    require!(self.min_decimals <= self.max_decimals, SwapError::InvalidDecimals);
+    require!(self.graduation_reserve_bps < DENOMINATOR, SwapError::InvalidSupply);
+    Ok(())
+  }
+
+  /// Checks a launch against the policy and splits `supply` into the curve allocation
+  /// and the graduation reserve
+  pub fn split_supply(&self, supply: u64, decimals: u8) -> Result<(u64, u64)> {
+    require!(supply != 0, SwapError::InvalidSupply);
+    require!(supply <= self.max_supply, SwapError::SupplyCapExceeded);
+    require!(
+      decimals >= self.min_decimals && decimals <= self.max_decimals,
+      SwapError::InvalidDecimals
+    );
+    let reserve = (supply as u128)
+      .checked_mul(self.graduation_reserve_bps as u128)
+      .and_then(|v| v.checked_div(DENOMINATOR as u128))
+      .ok_or(SwapError::CalculationFailure)?;
+    let reserve = u64::try_from(reserve).map_err(|_| SwapError::ConversionFailure)?;
+    Ok((supply - reserve, reserve))
+  }
+}
\ No newline at end of file
@@ -1,13 +1,219 @@
 use anchor_lang::prelude::*;
 
-use crate::{curve::LinearPriceCurve, Fees};
+use crate::{
+  curve::{to_u128, to_u64, DepthLevel, LinearPriceCurve},
+  error::SwapError,
+  Fees,
+  PairStats,
+  PauseMode,
+  AUTHORITY_SEED,
+};
 
 #[account]
 pub struct SwapPair {
   pub token_account: Pubkey,
-  pub native_account: Pubkey,
+  /// quote vault, holds the collateral bonded to the curve
+  pub quote_account: Pubkey,
   pub mint: Pubkey,
+  /// mint the pair is priced in, wsol or any allowlisted spl / token-2022 mint
+  pub quote_mint: Pubkey,
   pub curve: LinearPriceCurve,
   pub fees: Fees,
   pub bump: u8,
+  /// per pair circuit breaker, checked together with the global one
+  pub pause_mode: PauseMode,
+  /// launchpad namespace the pair trades under
+  pub app_stats: Pubkey,
+  /// quote amount the pair needs to hold before it can graduate, in quote mint decimals
+  pub graduation_threshold: u64,
+  /// quote bonded to the curve, only changed by swaps so direct vault transfers can't move the price
+  pub real_quote_reserves: u64,
+  /// tokens left on the curve, only changed by swaps
+  pub real_token_reserves: u64,
+  /// tokens bought off the curve and not sold back, the curve position every swap starts from
+  pub tokens_sold: u64,
+  /// tokens held in the vault outside the curve for graduation liquidity
+  pub graduation_reserve: u64,
+  pub stats: PairStats,
+  /// price observations account, written on every swap
+  pub observation_state: Pubkey,
+  /// set once the liquidity has moved to the pool, the curve no longer trades after
+  pub graduated: bool,
+}
+
+impl SwapPair {
+  /// Vault authority of the pair, derived from its mint and stored bump
+  pub fn authority(&self) -> Result<Pubkey> {
+    Pubkey::create_program_address(&[AUTHORITY_SEED, self.mint.as_ref(), &[self.bump]], &crate::ID)
+      .map_err(|_| SwapError::InvalidProgramAddress.into())
+  }
+
+  /// Quotes a buy with `quote_amount` (trade and transfer fees already taken out) from the stored curve position,
+  /// returns the quote the curve takes and the tokens it gives out
+  pub fn quote_buy(&self, quote_amount: u64) -> Result<(u64, u64)> {
+    let (quote_in, tokens_out) = self.curve
+      .swap_a_to_b_at_position(
+        to_u128(quote_amount)?,
+        to_u128(self.tokens_sold)?,
+        to_u128(self.real_quote_reserves)?,
+        to_u128(self.real_token_reserves)?
+      )?;
+    Ok((to_u64(quote_in)?, to_u64(tokens_out)?))
+  }
+
+  /// Quotes a sell of `token_amount` from the stored curve position,
+  /// returns the tokens the curve takes and the quote it gives out before fees
+  pub fn quote_sell(&self, token_amount: u64) -> Result<(u64, u64)> {
+    let (tokens_in, quote_out) = self.curve
+      .swap_b_to_a_at_position(
+        to_u128(token_amount)?,
+        to_u128(self.tokens_sold)?,
+        to_u128(self.real_quote_reserves)?
+      )?;
+    Ok((to_u64(tokens_in)?, to_u64(quote_out)?))
+  }
+
+  /// Current price of one whole token in quote base units, see `LinearPriceCurve::spot_price`
+  pub fn spot_price(&self, token_decimals: u8) -> Result<u64> {
+    let price = self.curve.spot_price(self.tokens_sold, token_decimals)?;
+    to_u64(price)
+  }
+
+  /// Value of `total_supply` at the current price, in quote base units
+  pub fn fully_diluted_market_cap(&self, total_supply: u64) -> Result<u64> {
+    let market_cap = self.curve.market_cap(self.tokens_sold, total_supply)?;
+    to_u64(market_cap)
+  }
+
+  /// Value of the tokens bought off the curve at the current price, in quote base units
+  pub fn circulating_market_cap(&self) -> Result<u64> {
+    let market_cap = self.curve.market_cap(self.tokens_sold, self.tokens_sold)?;
+    to_u64(market_cap)
+  }
+
+  /// Quote the curve needs to sell every token it has left, before fees
+  pub fn buyout_cost(&self) -> Result<u64> {
+    let cost = self.curve.buyout_cost(self.tokens_sold, self.real_token_reserves)?;
+    to_u64(cost)
+  }
+
+  /// Spot price levels over the curve allocation, the tokens sold plus the tokens left
+  pub fn depth_table(&self, step_bps: u16, token_decimals: u8) -> Result<Vec<DepthLevel>> {
+    let curve_supply = self.tokens_sold
+      .checked_add(self.real_token_reserves)
+      .ok_or(SwapError::CalculationFailure)?;
+    Ok(self.curve.depth_table(curve_supply, step_bps, token_decimals)?)
+  }
+
+  /// Books a buy of `tokens_out` for `quote_in` against the reserves
+  pub fn record_buy(&mut self, quote_in: u64, tokens_out: u64) -> Result<()> {
+    self.real_quote_reserves = self.real_quote_reserves
+      .checked_add(quote_in)
+      .ok_or(SwapError::CalculationFailure)?;
+    self.real_token_reserves = self.real_token_reserves
+      .checked_sub(tokens_out)
+      .ok_or(SwapError::CalculationFailure)?;
+    self.tokens_sold = self.tokens_sold
+      .checked_add(tokens_out)
+      .ok_or(SwapError::CalculationFailure)?;
+    Ok(())
+  }
+
+  /// Books a sell of `tokens_in` for `quote_out` (including fees) against the reserves
+  pub fn record_sell(&mut self, tokens_in: u64, quote_out: u64) -> Result<()> {
+    self.real_token_reserves = self.real_token_reserves
+      .checked_add(tokens_in)
+      .ok_or(SwapError::CalculationFailure)?;
+    self.real_quote_reserves = self.real_quote_reserves
+      .checked_sub(quote_out)
+      .ok_or(SwapError::CalculationFailure)?;
+    self.tokens_sold = self.tokens_sold
+      .checked_sub(tokens_in)
+      .ok_or(SwapError::CalculationFailure)?;
+    Ok(())
+  }
+  /// Tokens the pair deposits into the pool at graduation, its graduation reserve or, for a
+  /// launch that kept none back, the tokens left on the curve
+  pub fn graduation_deposit(&self) -> u64 {
+    if self.graduation_reserve > 0 {
+      self.graduation_reserve
+    } else {
+      self.real_token_reserves
+    }
+  }
+
+  /// Marks the pair graduated once its vaults have been burned and deposited into the pool,
+  /// the reserves go to zero with them
+  pub fn record_graduation(&mut self) {
+    self.graduated = true;
+    self.real_quote_reserves = 0;
+    self.real_token_reserves = 0;
+    self.tokens_sold = 0;
+    self.graduation_reserve = 0;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn pair() -> SwapPair {
+    SwapPair {
+      token_account: Pubkey::default(),
+      quote_account: Pubkey::default(),
+      mint: Pubkey::default(),
+      quote_mint: Pubkey::default(),
+      curve: LinearPriceCurve {
+        slope_numerator: 1,
+        slope_denominator: 200_000_000,
+        initial_token_a_price_numerator: 150,
+        initial_token_a_price_denominator: 3,
+      },
+      fees: Fees {
+        trade_fee_numerator: 0,
+        trade_fee_denominator: 0,
+        owner_trade_fee_numerator: 0,
+        owner_trade_fee_denominator: 0,
+        owner_withdraw_fee_numerator: 0,
+        owner_withdraw_fee_denominator: 0,
+        host_fee_numerator: 0,
+        host_fee_denominator: 0,
+      },
+      bump: 0,
+      pause_mode: PauseMode::Active,
+      app_stats: Pubkey::default(),
+      graduation_threshold: 85_000_000_000,
+      real_quote_reserves: 0,
+      real_token_reserves: 100_000_000_000,
+      tokens_sold: 0,
+      graduation_reserve: 20_000_000_000,
+      stats: PairStats::default(),
+      observation_state: Pubkey::default(),
+      graduated: false,
+    }
+  }
+
+  #[test]
+  fn test_graduation_deposit() {
+    let mut pair = pair();
+    assert_eq!(pair.graduation_deposit(), 20_000_000_000);
+    // without a reserve the unsold curve tokens seed the pool
+    pair.graduation_reserve = 0;
+    pair.record_buy(1_000, 40_000_000_000).unwrap();
+    assert_eq!(pair.graduation_deposit(), 60_000_000_000);
+  }
+
+  #[test]
+  fn test_record_graduation() {
+    let mut pair = pair();
+    let (quote_in, tokens_out) = pair.quote_buy(1_000_000_000).unwrap();
+    pair.record_buy(quote_in, tokens_out).unwrap();
+    assert_eq!(pair.tokens_sold, tokens_out);
+    pair.record_graduation();
+    assert!(pair.graduated);
+    assert_eq!(pair.real_quote_reserves, 0);
+    assert_eq!(pair.real_token_reserves, 0);
+    assert_eq!(pair.tokens_sold, 0);
+    assert_eq!(pair.graduation_reserve, 0);
+  }
 }
\ No newline at end of file
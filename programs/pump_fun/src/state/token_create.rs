@@ -1,11 +1,77 @@
 use anchor_lang::prelude::*;
 
+use crate::error::SwapError;
+
 
 #[account]
 pub struct TokenCreate {
   pub creator: Pubkey,
   pub mint: Pubkey,
+  /// current mint supply, lowered when the excess is burned at graduation
   pub supply: u64,
   pub bump: u8,
+  /// app state supply cap the token was launched under
   pub cap: u64,
+  /// launchpad namespace the token was created under
+  pub app_stats: Pubkey,
+  /// tokens on the bonding curve, cut down to the tokens sold once the pair graduates
+  pub curve_supply: u64,
+  /// tokens held back in the vault for graduation liquidity, the tokens deposited into the pool
+  /// once the pair graduates
+  pub reserve_supply: u64,
+  /// tokens burned at graduation
+  pub burned: u64,
+}
+
+impl TokenCreate {
+  /// Books a graduation that sold `tokens_sold` off the curve, deposited `deposited` into the
+  /// pool and burned `burned` from the vault
+  pub fn record_graduation(&mut self, tokens_sold: u64, deposited: u64, burned: u64) -> Result<()> {
+    self.supply = self.supply
+      .checked_sub(burned)
+      .ok_or(SwapError::CalculationFailure)?;
+    self.burned = self.burned
+      .checked_add(burned)
+      .ok_or(SwapError::CalculationFailure)?;
+    self.curve_supply = tokens_sold;
+    self.reserve_supply = deposited;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_record_graduation() {
+    let creator = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let app_stats = Pubkey::new_unique();
+    let mut token_create = TokenCreate {
+      creator,
+      mint,
+      supply: 1_000_000,
+      bump: 254,
+      cap: 2_000_000,
+      app_stats,
+      curve_supply: 800_000,
+      reserve_supply: 200_000,
+      burned: 0,
+    };
+    // 700k sold, the 100k left on the curve burned with the reserve deposited
+    token_create.record_graduation(700_000, 200_000, 100_000).unwrap();
+    assert_eq!(token_create.creator, creator);
+    assert_eq!(token_create.mint, mint);
+    assert_eq!(token_create.supply, 900_000);
+    assert_eq!(token_create.bump, 254);
+    assert_eq!(token_create.cap, 2_000_000);
+    assert_eq!(token_create.app_stats, app_stats);
+    assert_eq!(token_create.curve_supply, 700_000);
+    assert_eq!(token_create.reserve_supply, 200_000);
+    assert_eq!(token_create.burned, 100_000);
+    // the supply left is what was sold plus what went into the pool
+    assert_eq!(token_create.supply, token_create.curve_supply + token_create.reserve_supply);
+    assert!(token_create.record_graduation(0, 0, 1_000_000).is_err());
+  }
 }
\ No newline at end of file
@@ -0,0 +1,213 @@
+use anchor_lang::{ prelude::*, solana_program::program::invoke };
+use anchor_spl::{
+    token::ID as TOKEN_PROGRAM_ID,
+    token_2022::spl_token_2022::{
+        extension::{
+            default_account_state::DefaultAccountState,
+            mint_close_authority::MintCloseAuthority,
+            non_transferable::NonTransferable,
+            permanent_delegate::PermanentDelegate,
+            transfer_fee::{
+                instruction::harvest_withheld_tokens_to_mint,
+                TransferFeeAmount,
+                TransferFeeConfig,
+                MAX_FEE_BASIS_POINTS,
+            },
+            transfer_hook::{ self, TransferHook },
+            BaseStateWithExtensions,
+            StateWithExtensions,
+        },
+        onchain::invoke_transfer_checked,
+        state::{ Account as TokenAccountState, AccountState, Mint as MintState },
+    },
+    token_interface::{ Mint, TokenAccount },
+};
+
+use crate::{ error::SwapError, AppStats };
+
+/// Reads the transfer fee config of a token-2022 mint, `None` for spl token mints
+/// and token-2022 mints without the transfer fee extension
+fn transfer_fee_config(mint: &InterfaceAccount<Mint>) -> Result<Option<TransferFeeConfig>> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == TOKEN_PROGRAM_ID {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    Ok(mint_state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Fee the mint withholds when `amount` is transferred in the current epoch
+pub fn get_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let fee = match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(SwapError::FeeCalculationFailure)?,
+        None => 0,
+    };
+    Ok(fee)
+}
+
+/// Fee the mint withholds on a transfer that delivers exactly `net_amount` in the current epoch
+pub fn get_transfer_inverse_fee(mint: &InterfaceAccount<Mint>, net_amount: u64) -> Result<u64> {
+    let Some(config) = transfer_fee_config(mint)? else {
+        return Ok(0);
+    };
+    if net_amount == 0 {
+        return Ok(0);
+    }
+    let epoch = Clock::get()?.epoch;
+    let transfer_fee = config.get_epoch_fee(epoch);
+    // a 100% fee can't be inverted, every transfer loses the maximum fee
+    let fee = if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
+        u64::from(transfer_fee.maximum_fee)
+    } else {
+        config
+            .calculate_inverse_epoch_fee(epoch, net_amount)
+            .ok_or(SwapError::FeeCalculationFailure)?
+    };
+    Ok(fee)
+}
+
+/// Amount that arrives when `gross_amount` is transferred
+pub fn amount_after_transfer_fee(mint: &InterfaceAccount<Mint>, gross_amount: u64) -> Result<u64> {
+    gross_amount
+        .checked_sub(get_transfer_fee(mint, gross_amount)?)
+        .ok_or(SwapError::FeeCalculationFailure.into())
+}
+
+/// Amount to transfer so that `net_amount` arrives
+pub fn amount_before_transfer_fee(mint: &InterfaceAccount<Mint>, net_amount: u64) -> Result<u64> {
+    net_amount
+        .checked_add(get_transfer_inverse_fee(mint, net_amount)?)
+        .ok_or(SwapError::FeeCalculationFailure.into())
+}
+
+/// Transfer fees withheld in a token-2022 account, token-2022 refuses to close the account
+/// until they are harvested to the mint
+pub fn get_withheld_transfer_fees(account: &InterfaceAccount<TokenAccount>) -> Result<u64> {
+    let account_info = account.to_account_info();
+    if *account_info.owner == TOKEN_PROGRAM_ID {
+        return Ok(0);
+    }
+    let account_data = account_info.try_borrow_data()?;
+    let account_state = StateWithExtensions::<TokenAccountState>::unpack(&account_data)?;
+    let withheld = match account_state.get_extension::<TransferFeeAmount>() {
+        Ok(extension) => u64::from(extension.withheld_amount),
+        Err(_) => 0,
+    };
+    Ok(withheld)
+}
+
+/// Moves the transfer fees withheld in `account` to the mint, where the mint's withdraw
+/// authority collects them. Harvesting needs no signer
+pub fn harvest_withheld_transfer_fees<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    account: &InterfaceAccount<'info, TokenAccount>
+) -> Result<()> {
+    let instruction = harvest_withheld_tokens_to_mint(
+        token_program.key,
+        &mint.key(),
+        &[&account.key()]
+    )?;
+    invoke(
+        &instruction,
+        &[mint.to_account_info(), account.to_account_info()]
+    ).map_err(Into::into)
+}
+
+/// Rejects mints that would let someone other than the program freeze, close or move holders'
+/// tokens, or that can't be traded at all. Transfer fees and hooks are handled by the swaps,
+/// but only `pda` may change them once the token is launched
+pub fn check_mint_safety(mint: &InterfaceAccount<Mint>, pda: &Pubkey) -> Result<()> {
+    require!(mint.freeze_authority.is_none(), SwapError::InvalidFreezeAuthority);
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == TOKEN_PROGRAM_ID {
+        return Ok(());
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    if let Ok(extension) = mint_state.get_extension::<MintCloseAuthority>() {
+        require!(
+            Option::<Pubkey>::from(extension.close_authority).is_none(),
+            SwapError::InvalidCloseAuthority
+        );
+    }
+    if let Ok(extension) = mint_state.get_extension::<PermanentDelegate>() {
+        require!(
+            Option::<Pubkey>::from(extension.delegate).is_none(),
+            SwapError::PermanentDelegateNotAllowed
+        );
+    }
+    if let Ok(extension) = mint_state.get_extension::<DefaultAccountState>() {
+        require!(
+            extension.state != AccountState::Frozen as u8,
+            SwapError::DefaultAccountStateFrozen
+        );
+    }
+    require!(
+        mint_state.get_extension::<NonTransferable>().is_err(),
+        SwapError::NonTransferableMint
+    );
+    let held_by_pda = |authority: Option<Pubkey>| authority.is_none() || authority == Some(*pda);
+    if let Ok(extension) = mint_state.get_extension::<TransferFeeConfig>() {
+        require!(
+            held_by_pda(extension.transfer_fee_config_authority.into()),
+            SwapError::TransferFeeAuthorityNotAllowed
+        );
+    }
+    if let Ok(extension) = mint_state.get_extension::<TransferHook>() {
+        require!(
+            held_by_pda(extension.authority.into()),
+            SwapError::TransferHookAuthorityNotAllowed
+        );
+    }
+    Ok(())
+}
+
+/// Transfer hook program of a token-2022 mint, `None` when the mint has no hook
+pub fn get_transfer_hook_program(mint: &InterfaceAccount<Mint>) -> Result<Option<Pubkey>> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == TOKEN_PROGRAM_ID {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    Ok(transfer_hook::get_program_id(&mint_state))
+}
+
+/// Rejects mints whose transfer hook program is not on the app state allowlist,
+/// an unknown hook could refuse transfers into or out of the vaults and lock the pair
+pub fn check_transfer_hook(mint: &InterfaceAccount<Mint>, app_stats: &AppStats) -> Result<()> {
+    if let Some(hook_program) = get_transfer_hook_program(mint)? {
+        require!(app_stats.is_hook_program_allowed(&hook_program), SwapError::TransferHookNotAllowed);
+    }
+    Ok(())
+}
+
+/// `transfer_checked` through token-2022 that forwards the accounts the mint's transfer hook
+/// resolves from its extra-account-metas, passed in as remaining accounts
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'info>(
+    token_program: &AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]]
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint.to_account_info(),
+        to,
+        authority,
+        hook_accounts,
+        amount,
+        mint.decimals,
+        signer_seeds
+    ).map_err(Into::into)
+}
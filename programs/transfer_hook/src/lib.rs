@@ -0,0 +1,130 @@
+use anchor_lang::{ prelude::*, system_program::{ create_account, CreateAccount } };
+use anchor_spl::token_interface::{ Mint, TokenAccount };
+use spl_tlv_account_resolution::{ account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList };
+use spl_transfer_hook_interface::instruction::{ ExecuteInstruction, TransferHookInstruction };
+
+declare_id!("4zYaUUsV6CX7t32Q161YVGo1zGZpCBhDCxxiVAS3VGdX");
+
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+pub const COUNTER_SEED: &[u8] = b"counter";
+
+/// Minimal transfer hook that counts the transfers of a mint, used to test that
+/// pump-fun swaps forward the hook's extra accounts
+#[program]
+pub mod transfer_hook {
+    use super::*;
+
+    /*
+        create the extra account metas of the mint, the only extra account is the counter pda
+    */
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>
+    ) -> Result<()> {
+        let account_metas = vec![
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal { bytes: COUNTER_SEED.to_vec() },
+                    // index 1 of the execute accounts is the mint
+                    Seed::AccountKey { index: 1 },
+                ],
+                false,
+                true
+            )?
+        ];
+        let account_size = ExtraAccountMetaList::size_of(account_metas.len())?;
+        let lamports = Rent::get()?.minimum_balance(account_size);
+        let mint = ctx.accounts.mint.key();
+        let seeds: &[&[u8]; 3] = &[
+            EXTRA_ACCOUNT_METAS_SEED,
+            mint.as_ref(),
+            &[ctx.bumps.extra_account_meta_list],
+        ];
+        create_account(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), CreateAccount {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.extra_account_meta_list.to_account_info(),
+            }).with_signer(&[&seeds[..]]),
+            lamports,
+            account_size as u64,
+            ctx.program_id
+        )?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &account_metas
+        )?;
+        Ok(())
+    }
+
+    /*
+        called by token-2022 on every transfer of the mint
+    */
+    pub fn transfer_hook(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
+        ctx.accounts.counter.transfers += 1;
+        Ok(())
+    }
+
+    /// token-2022 calls the hook with the spl transfer hook interface discriminator,
+    /// route it to `transfer_hook`
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8]
+    ) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                __private::__global::transfer_hook(program_id, accounts, &amount.to_le_bytes())
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: created and written in the handler
+    #[account(mut, seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<Counter>(),
+        seeds = [COUNTER_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// account order is fixed by the transfer hook interface
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: owner of the source account, a pda for transfers out of the vault
+    pub owner: AccountInfo<'info>,
+
+    /// CHECK: extra account metas of the mint
+    #[account(seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    #[account(mut, seeds = [COUNTER_SEED, mint.key().as_ref()], bump)]
+    pub counter: Account<'info, Counter>,
+}
+
+#[account]
+pub struct Counter {
+    pub transfers: u64,
+}
@@ -1,10 +1,12 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { PumpFun } from '../target/types/pump_fun';
+import { TransferHook } from '../target/types/transfer_hook';
 import NodeWallet from "@coral-xyz/anchor/dist/cjs/nodewallet";
-import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, TransactionMessage, VersionedTransaction } from "@solana/web3.js";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction, TransactionMessage, VersionedTransaction } from "@solana/web3.js";
 import { BN } from "bn.js";
-import { closeAccount, createMint, createSyncNativeInstruction, getOrCreateAssociatedTokenAccount, NATIVE_MINT, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+import { closeAccount, createInitializeMintInstruction, createInitializeTransferFeeConfigInstruction, createInitializeTransferHookInstruction, createMint, createSyncNativeInstruction, ExtensionType, getAccount, getAssociatedTokenAddressSync, getMint, getMintLen, getOrCreateAssociatedTokenAccount, getTransferFeeAmount, getTransferFeeConfig, mintTo, NATIVE_MINT, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, transferChecked } from "@solana/spl-token";
 
 describe("pumpfun", () => {
   // Configure the client to use the local cluster.
@@ -14,13 +16,16 @@ describe("pumpfun", () => {
   const connection = provider.connection;
   const owner = provider.wallet as NodeWallet;
   const program = anchor.workspace.PumpFun as Program<PumpFun>;
+  const hookProgram = anchor.workspace.TransferHook as Program<TransferHook>;
 
   const user1 = Keypair.generate();
   const user2 = Keypair.generate();
 
+  const namespace = new BN(0);
   const appStats = PublicKey.findProgramAddressSync(
     [
-      anchor.utils.bytes.utf8.encode("app-stats")
+      anchor.utils.bytes.utf8.encode("app-stats"),
+      namespace.toArrayLike(Buffer, "le", 8)
     ],
     program.programId
   )[0];
@@ -51,8 +56,13 @@ describe("pumpfun", () => {
 
   const feeAccount = owner.publicKey;
   const wsol = NATIVE_MINT;
-  const nativeForPda = Keypair.generate();
-  const tokenNativeForPda = nativeForPda.publicKey;
+  const tokenQuoteForPda = PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode("quote-account"),
+      mint.toBuffer()
+    ],
+    program.programId
+  )[0];
   const pair = PublicKey.findProgramAddressSync(
     [
       anchor.utils.bytes.utf8.encode("swap-pair"),
@@ -60,6 +70,86 @@ describe("pumpfun", () => {
     ],
     program.programId
   )[0];
+  const observationState = PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode("price-observation"),
+      mint.toBuffer()
+    ],
+    program.programId
+  )[0];
+  // events are emitted through a self cpi, decode them from the inner instructions
+  const cpiEvents = async (signature: string) => {
+    const txInfo = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0
+    });
+    const keys = txInfo.transaction.message.getAccountKeys();
+    return txInfo.meta.innerInstructions
+      .flatMap((inner) => inner.instructions)
+      .filter((ix) => keys.get(ix.programIdIndex).equals(program.programId))
+      .map((ix) => program.coder.events.decode(
+        anchor.utils.bytes.base64.encode(anchor.utils.bytes.bs58.decode(ix.data).subarray(8))
+      ))
+      .filter((event) => event !== null);
+  };
+  // error code of a request that is expected to be rejected by the program
+  const errorCode = (request: Promise<unknown>) => request.then(() => null, (e) => e.error.errorCode.code);
+  // a fresh mint put through create_token, ready for its pair to be launched
+  const createLaunch = async (supply = new BN(100000 * 10 ** 6), decimals = 6) => {
+    const launchMint = Keypair.generate();
+    const launchPda = (seed: string) => PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode(seed), launchMint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [launchAuthority, launchBump] = launchPda("authority");
+    await createMint(
+      connection,
+      owner.payer,
+      launchAuthority,
+      null,
+      decimals,
+      launchMint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods.createToken(supply, launchBump).accounts({
+      authority: launchAuthority,
+      mint: launchMint.publicKey,
+      tokenAccountForPda: launchPda("token-account")[0],
+      tokenCreate: launchPda("token-create")[0],
+      feeAccount,
+      appStats,
+      tokenProgram: TOKEN_2022_PROGRAM_ID
+    }).rpc();
+    return {
+      bump: launchBump,
+      // initialize_linear_price accounts for a pair quoted in `quoteMint`
+      accounts: (quoteMint = wsol, tokenProgramQuote = TOKEN_PROGRAM_ID) => ({
+        creator: owner.publicKey,
+        pair: launchPda("swap-pair")[0],
+        observationState: launchPda("price-observation")[0],
+        mint: launchMint.publicKey,
+        quoteMint,
+        pda: launchAuthority,
+        tokenForPda: launchPda("token-account")[0],
+        tokenQuoteForPda: launchPda("quote-account")[0],
+        tokenCreate: launchPda("token-create")[0],
+        appStats,
+        tokenProgramMint: TOKEN_2022_PROGRAM_ID,
+        tokenProgramQuote
+      })
+    };
+  };
+  // compute units a confirmed transaction used, logged to track regressions
+  const computeUnits = async (label: string, signature: string) => {
+    await connection.confirmTransaction(signature, "confirmed");
+    const txInfo = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0
+    });
+    console.log(`${label}: ${txInfo.meta.computeUnitsConsumed} compute units`);
+    return txInfo.meta.computeUnitsConsumed;
+  };
   it("Setup", async () => {
     await connection.requestAirdrop(user1.publicKey, 5 * LAMPORTS_PER_SOL);
     await connection.requestAirdrop(user2.publicKey, 5 * LAMPORTS_PER_SOL);
@@ -68,19 +158,129 @@ describe("pumpfun", () => {
 
   // it("Is initialized!", async () => {
   //   // Add your test here.
-  //   const tx = await program.methods.initialize(new BN(0.02 * LAMPORTS_PER_SOL)).accounts({
+  //   const tx = await program.methods.initialize(namespace, new BN(0.02 * LAMPORTS_PER_SOL)).accounts({
   //     owner: owner.publicKey,
   //     appStats,
   //     feeAccount: owner.publicKey
   //   }).rpc();
   //   console.log("Your transaction signature", tx);
   // });
+  it("update config", async () => {
+    const tx = await program.methods.updateConfig({
+      feeLamports: null,
+      feeAccount: null,
+      feeBasisPoints: 100,
+      graduationThreshold: new BN(85 * LAMPORTS_PER_SOL)
+    }).accounts({
+      authority: owner.publicKey,
+      appStats,
+      governance: null,
+      proposal: null
+    }).rpc();
+    console.log(tx);
+  });
+  it("namespace", async () => {
+    const appStatsFor = (id: BN) => PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("app-stats"), id.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+    const programData = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    )[0];
+    const initialize = (id: BN, signer: Keypair, accounts) => program.methods.initialize(id, new BN(0.02 * LAMPORTS_PER_SOL)).accounts({
+      owner: signer.publicKey,
+      appStats: appStatsFor(id),
+      feeAccount: signer.publicKey,
+      programData: null,
+      rootAppStats: null,
+      ...accounts
+    }).signers([signer]).rpc();
+    // anyone else is turned away, whichever authority they point at
+    const newNamespace = new BN(Date.now());
+    assert.equal(await errorCode(initialize(newNamespace, user1, { rootAppStats: appStats })), "NamespaceNotAuthorized");
+    assert.equal(await errorCode(initialize(newNamespace, user1, { programData })), "NamespaceNotAuthorized");
+    assert.equal(await errorCode(initialize(newNamespace, user1, {})), "NamespaceNotAuthorized");
+    // the root owner and the upgrade authority can both open one
+    await initialize(newNamespace, owner.payer, { rootAppStats: appStats });
+    const tx = await initialize(newNamespace.addn(1), owner.payer, { programData });
+    console.log(tx);
+    const created = await program.account.appStats.fetch(appStatsFor(newNamespace));
+    assert.ok(created.owner.equals(owner.publicKey));
+    assert.equal(created.namespace.toString(), newNamespace.toString());
+  });
+  it("transfer ownership", async () => {
+    const proposeTx = await program.methods.proposeOwner(user1.publicKey).accounts({
+      authority: owner.publicKey,
+      appStats,
+      governance: null,
+      proposal: null
+    }).rpc();
+    const acceptTx = await program.methods.acceptOwner().accounts({
+      newOwner: user1.publicKey,
+      appStats
+    }).signers([user1]).rpc();
+    await connection.confirmTransaction(acceptTx, "confirmed");
+    const [proposed] = (await cpiEvents(proposeTx)).filter((event) => event.name === "OwnerProposed");
+    assert.ok(proposed.data.pendingOwner.equals(user1.publicKey));
+    const [accepted] = (await cpiEvents(acceptTx)).filter((event) => event.name === "OwnerAccepted");
+    assert.ok(accepted.data.previousOwner.equals(owner.publicKey));
+    assert.ok(accepted.data.owner.equals(user1.publicKey));
+    // hand it back so the rest of the suite keeps running as owner
+    await program.methods.proposeOwner(owner.publicKey).accounts({
+      authority: user1.publicKey,
+      appStats,
+      governance: null,
+      proposal: null
+    }).signers([user1]).rpc();
+    const tx = await program.methods.acceptOwner().accounts({
+      newOwner: owner.publicKey,
+      appStats
+    }).rpc();
+    console.log(tx);
+  });
   it("Create token", async () => {
+    // a mint with a freeze authority could freeze buyers' tokens, launch rejects it
+    const freezableMint = Keypair.generate();
+    const [freezableAuthority, freezableBump] = PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("authority"), freezableMint.publicKey.toBuffer()],
+      program.programId
+    );
+    await createMint(
+      connection,
+      owner.payer,
+      freezableAuthority,
+      owner.publicKey,
+      6,
+      freezableMint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const freezable = await program.methods.createToken(
+      new BN(100000 * 10 ** 6),
+      freezableBump
+    ).accounts({
+      authority: freezableAuthority,
+      mint: freezableMint.publicKey,
+      tokenAccountForPda: PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("token-account"), freezableMint.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      tokenCreate: PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("token-create"), freezableMint.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      feeAccount,
+      appStats,
+      tokenProgram: TOKEN_2022_PROGRAM_ID
+    }).rpc().then(() => null, (e) => e);
+    assert.equal(freezable.error.errorCode.code, "InvalidFreezeAuthority");
+
     await createMint(
       connection,
       owner.payer,
       authority,
-      authority,
+      null,
       6,
       mintKeypair,
       undefined,
@@ -99,25 +299,20 @@ describe("pumpfun", () => {
       tokenProgram: TOKEN_2022_PROGRAM_ID
     }).rpc().catch(e => console.log(e));
     console.log(tx);
+    // the supply is fixed once minted
+    const mintInfo = await getMint(connection, mint, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.isNull(mintInfo.mintAuthority);
+    const created = await program.account.tokenCreate.fetch(tokenCreate);
+    assert.equal(created.supply.toString(), mintInfo.supply.toString());
+    assert.equal(created.curveSupply.toString(), created.supply.toString());
   })
   it("linear price", async () => {
     console.log(authority.toBase58())
-    const transferIxn = SystemProgram.transfer({
-      fromPubkey: owner.publicKey,
-      toPubkey: tokenNativeForPda,
-      lamports: 2 * LAMPORTS_PER_SOL
-    });
-    const createAccountIxn = await program.methods.createAccount().accounts({
-      mint: wsol,
-      pda: authority,
-      tokenAccount: tokenNativeForPda
-    }).signers([nativeForPda]).instruction();
-    const syncNativeIxn = createSyncNativeInstruction(tokenNativeForPda);
     let slope_numerator = new BN(1);
     let slope_denominator = new BN(200000000);
     let r0_numerator = new BN(150);  // since R and C both have 8 decimals, we don't need to do any scaling here (starts at 50 base RLY price for every 1 base CC)
     let r0_denominator = new BN(3);  // no
-    const initLinearIxn = await program.methods.initializeLinearPrice(
+    const tx = await program.methods.initializeLinearPrice(
       slope_numerator,
       slope_denominator,
       r0_numerator,
@@ -125,26 +320,87 @@ describe("pumpfun", () => {
       bump,
     ).accounts({
       pair,
+      observationState,
       mint,
-      wsol,
+      quoteMint: wsol,
       pda: authority,
       tokenForPda: tokenAccountForPda,
-      tokenNativeForPda,
+      tokenQuoteForPda,
+      tokenCreate,
+      appStats,
       tokenProgramMint: TOKEN_2022_PROGRAM_ID,
-      tokenProgram: TOKEN_PROGRAM_ID
-    }).instruction();
-    let { blockhash } = await connection.getLatestBlockhash();
-    const instructions = [createAccountIxn, transferIxn, syncNativeIxn, initLinearIxn];
-    // Create the transaction message
-    const message = new TransactionMessage({
-      payerKey: owner.publicKey, // Public key of the account that will pay for the transaction
-      recentBlockhash: blockhash, // Latest blockhash
-      instructions, // Instructions included in transaction
-    }).compileToV0Message();
-    const transaction = new VersionedTransaction(message);
-    transaction.sign([owner.payer, nativeForPda]);
-    const txn = await connection.sendTransaction(transaction);
-    console.log(txn);
+      tokenProgramQuote: TOKEN_PROGRAM_ID
+    }).rpc();
+    console.log(tx);
+  });
+  let wsolFeeAccount: PublicKey;
+  it("wsol fee account", async () => {
+    // trade fees are paid to the fee wallet's token account for the pair's quote mint
+    wsolFeeAccount = (await getOrCreateAssociatedTokenAccount(
+      connection,
+      owner.payer,
+      NATIVE_MINT,
+      user2.publicKey
+    )).address;
+    const tx = await program.methods.updateConfig({
+      feeLamports: null,
+      feeAccount: user2.publicKey,
+      feeBasisPoints: null,
+      graduationThreshold: null
+    }).accounts({
+      authority: owner.publicKey,
+      appStats,
+      governance: null,
+      proposal: null
+    }).rpc();
+    console.log(tx);
+  });
+  it("rejected launches", async () => {
+    const launch = await createLaunch();
+    const launchLinear = (slopeNumerator: BN, creator: Keypair) => program.methods.initializeLinearPrice(
+      slopeNumerator,
+      new BN(200000000),
+      new BN(150),
+      new BN(3),
+      launch.bump
+    ).accounts({ ...launch.accounts(), creator: creator.publicKey }).signers([creator]).rpc();
+    // only the wallet that created the token can launch its pair
+    assert.equal(await errorCode(launchLinear(new BN(1), user1)), "NotTokenCreator");
+    // a flat curve, and one whose full supply costs more than a u64 of quote
+    assert.equal(await errorCode(launchLinear(new BN(0), owner.payer)), "InvalidCurve");
+    assert.equal(await errorCode(launchLinear(new BN("1000000000000000000"), owner.payer)), "InvalidCurve");
+    await launchLinear(new BN(1), owner.payer);
+  });
+  it("incorrect swap accounts", async () => {
+    // buys of the first pair's token with accounts of a second pair
+    const launch = await createLaunch();
+    await program.methods.initializeLinearPrice(
+      new BN(1),
+      new BN(200000000),
+      new BN(150),
+      new BN(3),
+      launch.bump
+    ).accounts(launch.accounts()).rpc();
+    const other = launch.accounts();
+    const buy = (accounts) => program.methods.swapToTokenNative(new BN(LAMPORTS_PER_SOL / 100), new BN(0)).accounts({
+      swapper: owner.publicKey,
+      pda: authority,
+      mint,
+      pair,
+      observationState,
+      tokenAccountForSwapper: getAssociatedTokenAddressSync(mint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      tokenAccountForPda,
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID,
+      ...accounts
+    }).rpc();
+    assert.equal(await errorCode(buy({ tokenAccountForPda: other.tokenForPda })), "IncorrectSwapAccount");
+    assert.equal(await errorCode(buy({ quoteAccountForPda: other.tokenQuoteForPda })), "IncorrectSwapAccount");
+    assert.equal(await errorCode(buy({ observationState: other.observationState })), "IncorrectSwapAccount");
+    assert.equal(await errorCode(buy({ pda: other.pda })), "IncorrectSwapAccount");
+    assert.equal(await errorCode(buy({ pda: owner.publicKey })), "IncorrectSwapAccount");
   });
   it ("swap to token", async () => {
     const tokenAccountForSwapper = await getOrCreateAssociatedTokenAccount(
@@ -169,16 +425,20 @@ describe("pumpfun", () => {
       lamports: LAMPORTS_PER_SOL
     });
     const syncNativeIxn = createSyncNativeInstruction(nativeAccountForSwapper.address);
-    const swapIxn = await program.methods.swapToToken(new BN(LAMPORTS_PER_SOL)).accounts({
+    const swapIxn = await program.methods.swapToToken(new BN(LAMPORTS_PER_SOL), new BN(0)).accounts({
       pda: authority,
       mint,
-      wsol,
+      quoteMint: wsol,
       pair,
+      observationState,
       tokenAccountForSwapper: tokenAccountForSwapper.address,
-      nativeAccountForSwapper: nativeAccountForSwapper.address,
+      quoteAccountForSwapper: nativeAccountForSwapper.address,
       tokenAccountForPda,
-      nativeAccountForPda: tokenNativeForPda,
-      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID,
+      tokenProgramQuote: TOKEN_PROGRAM_ID
     }).instruction();
     let { blockhash } = await connection.getLatestBlockhash();
     const instructions = [transferIxn, syncNativeIxn, swapIxn];
@@ -210,16 +470,20 @@ describe("pumpfun", () => {
       NATIVE_MINT,
       owner.publicKey
     );
-    const swapIxn = await program.methods.swapToSol(new BN(10)).accounts({
+    const swapIxn = await program.methods.swapToSol(new BN(10), new BN(0), false).accounts({
       pda: authority,
       mint,
-      wsol,
+      quoteMint: wsol,
       pair,
+      observationState,
       tokenAccountForSwapper: tokenAccountForSwapper.address,
-      nativeAccountForSwapper: nativeAccountForSwapper.address,
+      quoteAccountForSwapper: nativeAccountForSwapper.address,
       tokenAccountForPda,
-      nativeAccountForPda: tokenNativeForPda,
-      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID,
+      tokenProgramQuote: TOKEN_PROGRAM_ID
     }).instruction();
     let { blockhash } = await connection.getLatestBlockhash();
     const instructions = [swapIxn];
@@ -241,4 +505,988 @@ describe("pumpfun", () => {
       owner.publicKey
     );
   })
+  it("swap native sol", async () => {
+    // user1 has never held the token, the buy creates the associated token account
+    const tokenAccountForSwapper = getAssociatedTokenAddressSync(
+      mint,
+      user1.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    // asking for more tokens than the curve gives out fails on slippage
+    const slippage = await program.methods.swapToTokenNative(new BN(LAMPORTS_PER_SOL), new BN("18446744073709551615")).accounts({
+      swapper: user1.publicKey,
+      pda: authority,
+      mint,
+      pair,
+      observationState,
+      tokenAccountForSwapper,
+      tokenAccountForPda,
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).signers([user1]).rpc().then(() => null, (e) => e);
+    assert.equal(slippage.error.errorCode.code, "ExceededSlippage");
+    const buyTx = await program.methods.swapToTokenNative(new BN(LAMPORTS_PER_SOL), new BN(1)).accounts({
+      swapper: user1.publicKey,
+      pda: authority,
+      mint,
+      pair,
+      observationState,
+      tokenAccountForSwapper,
+      tokenAccountForPda,
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).signers([user1]).rpc();
+    await computeUnits("native buy", buyTx);
+    const unwrapAccount = PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("unwrap-account"),
+        user1.publicKey.toBuffer()
+      ],
+      program.programId
+    )[0];
+    const balance = await connection.getTokenAccountBalance(tokenAccountForSwapper);
+    // selling everything with close_if_empty hands the account rent back to user1
+    const tx = await program.methods.swapToSolNative(new BN(balance.value.amount), new BN(0), true).accounts({
+      swapper: user1.publicKey,
+      pda: authority,
+      mint,
+      wsol,
+      pair,
+      observationState,
+      tokenAccountForSwapper,
+      unwrapAccount,
+      tokenAccountForPda,
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).signers([user1]).rpc();
+    console.log(tx);
+    assert.isNull(await connection.getAccountInfo(tokenAccountForSwapper));
+    await connection.confirmTransaction(tx, "confirmed");
+    await computeUnits("native sell", tx);
+    const [trade] = (await cpiEvents(tx)).filter((event) => event.name === "Trade");
+    assert.ok(trade.data.trader.equals(user1.publicKey));
+    assert.ok("sell" in trade.data.side);
+    assert.equal(trade.data.amountIn.toString(), balance.value.amount);
+    const { stats } = await program.account.swapPair.fetch(pair);
+    assert.ok(stats.lastTrader.equals(user1.publicKey));
+    assert.ok(stats.sellVolumeTokens.gte(new BN(balance.value.amount)));
+    assert.equal(stats.lastPrice.toString(), trade.data.spotPrice.toString());
+    assert.isAbove(stats.buyCount.toNumber(), 0);
+    const globalStats = await program.account.appStats.fetch(appStats);
+    assert.isAbove(globalStats.tokensLaunched.toNumber(), 0);
+    assert.isAbove(globalStats.totalProtocolFees.toNumber(), 0);
+    const observations = await program.account.priceObservations.fetch(observationState);
+    assert.ok(observations.pair.equals(pair));
+    assert.equal(observations.lastPrice.toString(), stats.lastPrice.toString());
+    assert.isAtLeast(observations.count, 1);
+  })
+  it("precise math compute units", async () => {
+    // the pair's own buy quote, from a plain buy up to a buyout of the curve. A build with the
+    // compute-bench feature runs the same math without the u128 fast path, so the units logged
+    // by the two builds show what it saves
+    const quoteAccounts = { pair, mint, quoteMint: wsol, appStats };
+    const oversized = new BN(100_000_000).mul(new BN(LAMPORTS_PER_SOL));
+    for (const amountIn of [new BN(LAMPORTS_PER_SOL), new BN(10 * LAMPORTS_PER_SOL), oversized]) {
+      const quote = await program.methods.quoteBuy(amountIn).accounts(quoteAccounts).view();
+      assert.isAbove(quote.curveTokens.toNumber(), 0);
+      const units = await computeUnits(
+        `quote buy of ${amountIn.toString()}`,
+        await program.methods.quoteBuy(amountIn).accounts(quoteAccounts).rpc()
+      );
+      // in either build a quote fits the default compute budget of an instruction
+      assert.isBelow(units, 200_000);
+    }
+  });
+  it("quotes", async () => {
+    const quoteAccounts = { pair, mint, quoteMint: wsol, appStats };
+    const quote = await program.methods.quoteBuy(new BN(LAMPORTS_PER_SOL)).accounts(quoteAccounts).view();
+    assert.equal(quote.amountIn.toString(), LAMPORTS_PER_SOL.toString());
+    assert.isAbove(quote.amountOut.toNumber(), 0);
+    const spotPrice = await program.methods.getSpotPrice().accounts(quoteAccounts).view();
+    const marketCap = await program.methods.getMarketCap().accounts(quoteAccounts).view();
+    assert.isAbove(spotPrice.toNumber(), 0);
+    assert.isAbove(marketCap.toNumber(), 0);
+    // the swap fills exactly what the quote promised
+    await program.methods.swapToTokenNative(new BN(LAMPORTS_PER_SOL), quote.amountOut).accounts({
+      pda: authority,
+      mint,
+      pair,
+      observationState,
+      tokenAccountForSwapper: getAssociatedTokenAddressSync(mint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      tokenAccountForPda,
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).rpc();
+    const sellQuote = await program.methods.quoteSell(quote.amountOut).accounts(quoteAccounts).view();
+    assert.ok(sellQuote.amountOut.lt(quote.amountIn));
+    const after = await program.methods.getSpotPrice().accounts(quoteAccounts).view();
+    assert.ok(after.gt(spotPrice));
+    // a buyout only spends part of the input, the fee follows what the curve takes
+    const oversized = new BN(100_000_000).mul(new BN(LAMPORTS_PER_SOL));
+    const buyout = await program.methods.quoteBuy(oversized).accounts(quoteAccounts).view();
+    const { realTokenReserves } = await program.account.swapPair.fetch(pair);
+    const { feeBasisPoints } = await program.account.appStats.fetch(appStats);
+    assert.equal(buyout.curveTokens.toString(), realTokenReserves.toString());
+    assert.ok(buyout.amountIn.lt(oversized));
+    assert.equal(buyout.amountIn.toString(), buyout.curveQuote.add(buyout.tradeFee).toString());
+    const expectedFee = buyout.amountIn.muln(feeBasisPoints).divn(10000);
+    assert.isAtMost(buyout.tradeFee.sub(expectedFee).abs().toNumber(), 1);
+    // buying out the curve costs what a buyout quote puts on it
+    const buyoutCost = await program.methods.getBuyoutCost().accounts(quoteAccounts).view();
+    assert.isAtMost(buyoutCost.sub(buyout.curveQuote).abs().toNumber(), 1);
+    // only the tokens bought circulate, so they're worth less than the whole supply
+    const circulating = await program.methods.getCirculatingMarketCap().accounts(quoteAccounts).view();
+    assert.isAbove(circulating.toNumber(), 0);
+    assert.ok(circulating.lt(await program.methods.getMarketCap().accounts(quoteAccounts).view()));
+    // quarter steps from nothing sold to the whole curve allocation
+    const depth = await program.methods.getDepthTable(2500).accounts(quoteAccounts).view();
+    const { tokensSold } = await program.account.swapPair.fetch(pair);
+    assert.equal(depth.length, 5);
+    assert.equal(depth[0].tokensSold.toString(), "0");
+    assert.equal(depth[4].tokensSold.toString(), tokensSold.add(realTokenReserves).toString());
+    depth.slice(1).forEach((level, i) => assert.ok(level.price.gt(depth[i].price)));
+    // a table too long for the return data is refused, views fail as a simulation so check its logs
+    const tooLong = await program.methods.getDepthTable(100).accounts(quoteAccounts).view().then(() => null, (e) => e);
+    assert.include((tooLong.simulationResponse?.logs ?? tooLong.logs).join("\n"), "InvalidInput");
+  });
+  it("skim", async () => {
+    const quoteAccounts = { pair, mint, quoteMint: wsol, appStats };
+    const feeTokenAccount = (await getOrCreateAssociatedTokenAccount(
+      connection,
+      owner.payer,
+      mint,
+      user2.publicKey,
+      undefined,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    )).address;
+    const vaults = async () => ({
+      tokens: new BN((await connection.getTokenAccountBalance(tokenAccountForPda)).value.amount),
+      quote: new BN((await connection.getTokenAccountBalance(tokenQuoteForPda)).value.amount)
+    });
+    const treasury = async () => ({
+      tokens: new BN((await connection.getTokenAccountBalance(feeTokenAccount)).value.amount),
+      quote: new BN((await connection.getTokenAccountBalance(wsolFeeAccount)).value.amount)
+    });
+    // the swaps so far booked every vault movement against the reserves, nothing to skim
+    const reserves = await program.account.swapPair.fetch(pair);
+    const booked = {
+      tokens: reserves.realTokenReserves.add(reserves.graduationReserve),
+      quote: reserves.realQuoteReserves
+    };
+    let balances = await vaults();
+    assert.equal(balances.tokens.toString(), booked.tokens.toString());
+    assert.equal(balances.quote.toString(), booked.quote.toString());
+    const quoteBefore = await program.methods.quoteBuy(new BN(LAMPORTS_PER_SOL)).accounts(quoteAccounts).view();
+
+    // donate tokens and quote straight into the vaults
+    const tokenDonation = new BN(10 ** 6);
+    const quoteDonation = new BN(LAMPORTS_PER_SOL / 10);
+    await transferChecked(
+      connection,
+      owner.payer,
+      getAssociatedTokenAddressSync(mint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      mint,
+      tokenAccountForPda,
+      owner.publicKey,
+      tokenDonation.toNumber(),
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await provider.sendAndConfirm(new Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: owner.publicKey,
+        toPubkey: tokenQuoteForPda,
+        lamports: quoteDonation.toNumber()
+      }),
+      createSyncNativeInstruction(tokenQuoteForPda)
+    ));
+    balances = await vaults();
+    assert.equal(balances.tokens.toString(), booked.tokens.add(tokenDonation).toString());
+    assert.equal(balances.quote.toString(), booked.quote.add(quoteDonation).toString());
+    // the curve prices off the booked reserves, a donation does not move the quote
+    const quoteDonated = await program.methods.quoteBuy(new BN(LAMPORTS_PER_SOL)).accounts(quoteAccounts).view();
+    assert.equal(quoteDonated.amountOut.toString(), quoteBefore.amountOut.toString());
+    assert.equal(quoteDonated.curveQuote.toString(), quoteBefore.curveQuote.toString());
+
+    const skim = () => program.methods.skim().accounts({
+      pda: authority,
+      mint,
+      quoteMint: wsol,
+      pair,
+      tokenAccountForPda,
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      feeTokenAccount,
+      appStats,
+      tokenProgramQuote: TOKEN_PROGRAM_ID,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).rpc();
+    const treasuryBefore = await treasury();
+    const tx = await skim();
+    console.log(tx);
+    // only the donation reaches the treasury, the reserves stay in the vaults
+    let treasuryAfter = await treasury();
+    assert.equal(treasuryAfter.tokens.sub(treasuryBefore.tokens).toString(), tokenDonation.toString());
+    assert.equal(treasuryAfter.quote.sub(treasuryBefore.quote).toString(), quoteDonation.toString());
+    balances = await vaults();
+    assert.equal(balances.tokens.toString(), booked.tokens.toString());
+    assert.equal(balances.quote.toString(), booked.quote.toString());
+    const skimmed = await program.account.swapPair.fetch(pair);
+    assert.equal(skimmed.realTokenReserves.toString(), reserves.realTokenReserves.toString());
+    assert.equal(skimmed.realQuoteReserves.toString(), reserves.realQuoteReserves.toString());
+    assert.equal(skimmed.graduationReserve.toString(), reserves.graduationReserve.toString());
+    const quoteAfter = await program.methods.quoteBuy(new BN(LAMPORTS_PER_SOL)).accounts(quoteAccounts).view();
+    assert.equal(quoteAfter.amountOut.toString(), quoteBefore.amountOut.toString());
+
+    // with no surplus left a second skim moves nothing
+    await skim();
+    treasuryAfter = await treasury();
+    assert.equal(treasuryAfter.tokens.sub(treasuryBefore.tokens).toString(), tokenDonation.toString());
+    assert.equal(treasuryAfter.quote.sub(treasuryBefore.quote).toString(), quoteDonation.toString());
+    balances = await vaults();
+    assert.equal(balances.tokens.toString(), booked.tokens.toString());
+    assert.equal(balances.quote.toString(), booked.quote.toString());
+  });
+  it("curve preset", async () => {
+    const curvePresetFor = (id: BN) => PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("curve-preset"),
+        appStats.toBuffer(),
+        id.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    )[0];
+    const setPreset = (id: BN, label: string, enabled: boolean) => {
+      const name = Buffer.alloc(32);
+      name.write(label);
+      return program.methods.setCurvePreset(id, {
+        name: [...name],
+        curveType: { linear: {} },
+        curve: {
+          slopeNumerator: new BN(1),
+          slopeDenominator: new BN(200000000),
+          initialTokenAPriceNumerator: new BN(150),
+          initialTokenAPriceDenominator: new BN(3)
+        },
+        supply: new BN(100000 * 10 ** 6),
+        graduationThreshold: new BN(85 * LAMPORTS_PER_SOL),
+        enabled
+      }).accounts({
+        authority: owner.publicKey,
+        appStats,
+        curvePreset: curvePresetFor(id),
+        governance: null,
+        proposal: null
+      }).rpc();
+    };
+    const tx = await setPreset(new BN(1), "default linear", true);
+    console.log(tx);
+    await connection.confirmTransaction(tx, "confirmed");
+    const [presetSet] = (await cpiEvents(tx)).filter((event) => event.name === "CurvePresetSet");
+    assert.ok(presetSet.data.curvePreset.equals(curvePresetFor(new BN(1))));
+    assert.equal(presetSet.data.presetId.toString(), "1");
+    assert.isTrue(presetSet.data.params.enabled);
+    await setPreset(new BN(2), "retired linear", false);
+
+    const launch = await createLaunch();
+    const launchWithPreset = (id: BN) => program.methods.initializeLinearPriceWithPreset(id, launch.bump).accounts({
+      launch: launch.accounts(),
+      curvePreset: curvePresetFor(id)
+    }).rpc();
+    assert.equal(await errorCode(launchWithPreset(new BN(2))), "CurvePresetDisabled");
+    // no preset was ever published under this id
+    assert.equal(await errorCode(launchWithPreset(new BN(99))), "AccountNotInitialized");
+
+    // custom curves have to stay inside the owner's bounds
+    const setBounds = (bounds) => program.methods.setCurveBounds(bounds).accounts({
+      authority: owner.publicKey,
+      appStats,
+      governance: null,
+      proposal: null
+    }).rpc();
+    const launchCustom = (slopeNumerator: number, priceNumerator: number) => program.methods.initializeLinearPrice(
+      new BN(slopeNumerator),
+      new BN(200000000),
+      new BN(priceNumerator),
+      new BN(3),
+      launch.bump
+    ).accounts(launch.accounts()).rpc();
+    const bounds = {
+      allowCustom: true,
+      minSlope: { numerator: new BN(1), denominator: new BN(200000000) },
+      maxSlope: { numerator: new BN(2), denominator: new BN(200000000) },
+      minInitialPrice: { numerator: new BN(100), denominator: new BN(3) },
+      maxInitialPrice: { numerator: new BN(200), denominator: new BN(3) }
+    };
+    const boundsTx = await setBounds(bounds);
+    await connection.confirmTransaction(boundsTx, "confirmed");
+    const [boundsSet] = (await cpiEvents(boundsTx)).filter((event) => event.name === "CurveBoundsSet");
+    assert.equal(boundsSet.data.bounds.maxSlope.numerator.toString(), "2");
+    assert.equal(await errorCode(launchCustom(3, 150)), "CurveOutOfBounds");
+    assert.equal(await errorCode(launchCustom(1, 300)), "CurveOutOfBounds");
+    assert.equal(await errorCode(launchCustom(1, 50)), "CurveOutOfBounds");
+    await setBounds({ ...bounds, allowCustom: false });
+    assert.equal(await errorCode(launchCustom(1, 150)), "UnsupportedCurveType");
+
+    // presets launch while custom curves are switched off
+    await launchWithPreset(new BN(1));
+    const launched = await program.account.swapPair.fetch(launch.accounts().pair);
+    assert.equal(launched.curve.slopeNumerator.toString(), "1");
+    assert.equal(launched.curve.slopeDenominator.toString(), "200000000");
+    assert.equal(launched.graduationThreshold.toString(), (85 * LAMPORTS_PER_SOL).toString());
+    await setBounds({
+      allowCustom: true,
+      minSlope: { numerator: new BN(0), denominator: new BN(1) },
+      maxSlope: { numerator: new BN("18446744073709551615"), denominator: new BN(1) },
+      minInitialPrice: { numerator: new BN(0), denominator: new BN(1) },
+      maxInitialPrice: { numerator: new BN("18446744073709551615"), denominator: new BN(1) }
+    });
+  })
+  it("pause", async () => {
+    const setPause = (mode) => program.methods.setPause(mode).accounts({
+      authority: owner.publicKey,
+      appStats,
+      governance: null,
+      proposal: null
+    }).rpc();
+    const setPairPause = (mode) => program.methods.setPairPause(mode).accounts({
+      authority: owner.publicKey,
+      appStats,
+      pair,
+      governance: null,
+      proposal: null
+    }).rpc();
+    const pauseChanged = async (tx: string) => {
+      await connection.confirmTransaction(tx, "confirmed");
+      const [event] = (await cpiEvents(tx)).filter((event) => event.name === "PauseChanged");
+      return event.data;
+    };
+    const buy = () => program.methods.swapToTokenNative(new BN(LAMPORTS_PER_SOL / 100), new BN(0)).accounts({
+      swapper: owner.publicKey,
+      pda: authority,
+      mint,
+      pair,
+      observationState,
+      tokenAccountForSwapper: getAssociatedTokenAddressSync(mint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      tokenAccountForPda,
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).rpc();
+    const sell = () => program.methods.swapToSolNative(new BN(10 ** 6), new BN(0), false).accounts({
+      swapper: owner.publicKey,
+      pda: authority,
+      mint,
+      wsol,
+      pair,
+      observationState,
+      tokenAccountForSwapper: getAssociatedTokenAddressSync(mint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      unwrapAccount: PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("unwrap-account"), owner.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      tokenAccountForPda,
+      quoteAccountForPda: tokenQuoteForPda,
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).rpc();
+    const create = async () => {
+      const pausedMint = Keypair.generate();
+      const pausedPda = (seed: string) => PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode(seed), pausedMint.publicKey.toBuffer()],
+        program.programId
+      );
+      await createMint(
+        connection,
+        owner.payer,
+        pausedPda("authority")[0],
+        null,
+        6,
+        pausedMint,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      return program.methods.createToken(new BN(100000 * 10 ** 6), pausedPda("authority")[1]).accounts({
+        authority: pausedPda("authority")[0],
+        mint: pausedMint.publicKey,
+        tokenAccountForPda: pausedPda("token-account")[0],
+        tokenCreate: pausedPda("token-create")[0],
+        feeAccount,
+        appStats,
+        tokenProgram: TOKEN_2022_PROGRAM_ID
+      }).rpc();
+    };
+
+    // halted stops everything
+    const halted = await pauseChanged(await setPause({ halted: {} }));
+    assert.isNull(halted.mint);
+    assert.ok("active" in halted.previousMode);
+    assert.ok("halted" in halted.mode);
+    assert.equal(await errorCode(buy()), "TradingPaused");
+    assert.equal(await errorCode(sell()), "TradingPaused");
+    assert.equal(await errorCode(create()), "CreatePaused");
+
+    // sell only lets holders exit and nothing else
+    const sellOnly = await pauseChanged(await setPause({ sellOnly: {} }));
+    assert.ok("halted" in sellOnly.previousMode);
+    assert.ok("sellOnly" in sellOnly.mode);
+    assert.equal(await errorCode(buy()), "TradingPaused");
+    assert.equal(await errorCode(create()), "CreatePaused");
+    await sell();
+
+    // create disabled keeps trading open
+    await setPause({ createDisabled: {} });
+    assert.equal(await errorCode(create()), "CreatePaused");
+    await buy();
+    await sell();
+    await setPause({ active: {} });
+
+    // a pair's own breaker applies on top of the global one
+    const pairHalted = await pauseChanged(await setPairPause({ halted: {} }));
+    assert.ok(pairHalted.mint.equals(mint));
+    assert.ok("active" in pairHalted.previousMode);
+    assert.ok("halted" in pairHalted.mode);
+    assert.equal(await errorCode(buy()), "TradingPaused");
+    assert.equal(await errorCode(sell()), "TradingPaused");
+    await setPairPause({ sellOnly: {} });
+    assert.equal(await errorCode(buy()), "TradingPaused");
+    await sell();
+    const tx = await setPairPause({ active: {} });
+    console.log(tx);
+    await buy();
+  })
+  it("quote mint", async () => {
+    // a 6 decimal stablecoin style quote mint
+    const quoteMint = await createMint(connection, owner.payer, owner.publicKey, null, 6);
+    const setQuoteMint = (allowed: boolean) => program.methods.setQuoteMint(allowed).accounts({
+      authority: owner.publicKey,
+      appStats,
+      quoteMint,
+      governance: null,
+      proposal: null
+    }).rpc();
+    const allowTx = await setQuoteMint(true);
+    await connection.confirmTransaction(allowTx, "confirmed");
+    const [allowed] = (await cpiEvents(allowTx)).filter((event) => event.name === "QuoteMintSet");
+    assert.ok(allowed.data.quoteMint.equals(quoteMint));
+    assert.isTrue(allowed.data.allowed);
+    let stats = await program.account.appStats.fetch(appStats);
+    assert.equal(stats.quoteMintCount, 2);
+    assert.ok(stats.quoteMints[1].equals(quoteMint));
+
+    // the wsol curve is rescaled to the quote decimals at launch
+    const launch = await createLaunch();
+    const accounts = launch.accounts(quoteMint);
+    await program.methods.initializeLinearPrice(
+      new BN(1),
+      new BN(200000000),
+      new BN(150),
+      new BN(3),
+      launch.bump
+    ).accounts(accounts).rpc();
+    const launched = await program.account.swapPair.fetch(accounts.pair);
+    assert.ok(launched.quoteMint.equals(quoteMint));
+    assert.equal(launched.curve.slopeDenominator.toString(), "200000000000");
+    assert.equal(launched.curve.initialTokenAPriceDenominator.toString(), "3000");
+    assert.equal(launched.graduationThreshold.toString(), (85 * 10 ** 6).toString());
+
+    const quoteAccountForSwapper = (await getOrCreateAssociatedTokenAccount(
+      connection,
+      owner.payer,
+      quoteMint,
+      owner.publicKey
+    )).address;
+    const feeQuoteAccount = (await getOrCreateAssociatedTokenAccount(
+      connection,
+      owner.payer,
+      quoteMint,
+      user2.publicKey
+    )).address;
+    await mintTo(connection, owner.payer, quoteMint, quoteAccountForSwapper, owner.payer, 1000 * 10 ** 6);
+    const tokenAccountForSwapper = getAssociatedTokenAddressSync(accounts.mint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    const swapAccounts = {
+      swapper: owner.publicKey,
+      pda: accounts.pda,
+      mint: accounts.mint,
+      quoteMint,
+      pair: accounts.pair,
+      observationState: accounts.observationState,
+      tokenAccountForSwapper,
+      quoteAccountForSwapper,
+      tokenAccountForPda: accounts.tokenForPda,
+      quoteAccountForPda: accounts.tokenQuoteForPda,
+      feeQuoteAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID,
+      tokenProgramQuote: TOKEN_PROGRAM_ID
+    };
+    const quoteAccounts = { pair: accounts.pair, mint: accounts.mint, quoteMint, appStats };
+    const balance = async (account: PublicKey) => new BN((await connection.getTokenAccountBalance(account)).value.amount);
+
+    // a 10 usdc buy moves exactly the quoted amounts
+    const buyQuote = await program.methods.quoteBuy(new BN(10 * 10 ** 6)).accounts(quoteAccounts).view();
+    assert.isAbove(buyQuote.amountOut.toNumber(), 0);
+    const quoteBefore = await balance(quoteAccountForSwapper);
+    const feeBefore = await balance(feeQuoteAccount);
+    await program.methods.swapToToken(new BN(10 * 10 ** 6), buyQuote.amountOut).accounts(swapAccounts).rpc();
+    assert.equal((await balance(tokenAccountForSwapper)).toString(), buyQuote.amountOut.toString());
+    assert.equal(quoteBefore.sub(await balance(quoteAccountForSwapper)).toString(), buyQuote.amountIn.toString());
+    assert.equal((await balance(feeQuoteAccount)).sub(feeBefore).toString(), buyQuote.tradeFee.toString());
+    assert.equal((await balance(accounts.tokenQuoteForPda)).toString(), buyQuote.curveQuote.toString());
+
+    // and selling it all back pays out the quoted usdc
+    const sellQuote = await program.methods.quoteSell(buyQuote.amountOut).accounts(quoteAccounts).view();
+    assert.isAbove(sellQuote.amountOut.toNumber(), 0);
+    assert.ok(sellQuote.amountOut.lt(buyQuote.amountIn));
+    const quoteBeforeSell = await balance(quoteAccountForSwapper);
+    await program.methods.swapToSol(buyQuote.amountOut, sellQuote.amountOut, false).accounts(swapAccounts).rpc();
+    assert.equal((await balance(tokenAccountForSwapper)).toString(), "0");
+    assert.equal((await balance(quoteAccountForSwapper)).sub(quoteBeforeSell).toString(), sellQuote.amountOut.toString());
+    const { realQuoteReserves, tokensSold } = await program.account.swapPair.fetch(accounts.pair);
+    assert.equal(tokensSold.toString(), "0");
+    assert.equal((await balance(accounts.tokenQuoteForPda)).toString(), realQuoteReserves.toString());
+
+    const tx = await setQuoteMint(false);
+    console.log(tx);
+    stats = await program.account.appStats.fetch(appStats);
+    assert.equal(stats.quoteMintCount, 1);
+    assert.ok(stats.quoteMints[0].equals(NATIVE_MINT));
+  });
+  it("transfer hook", async () => {
+    const hookMintKeypair = Keypair.generate();
+    const hookMint = hookMintKeypair.publicKey;
+    const hookPda = (seed: string) => PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode(seed), hookMint.toBuffer()],
+      program.programId
+    )[0];
+    const [hookAuthority, hookBump] = PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("authority"), hookMint.toBuffer()],
+      program.programId
+    );
+    const extraAccountMetaList = PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("extra-account-metas"), hookMint.toBuffer()],
+      hookProgram.programId
+    )[0];
+    const counter = PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("counter"), hookMint.toBuffer()],
+      hookProgram.programId
+    )[0];
+
+    const mintLen = getMintLen([ExtensionType.TransferHook]);
+    const createHookMint = async (keypair: Keypair, mintAuthority: PublicKey, extensionAuthority: PublicKey) => provider.sendAndConfirm(new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: owner.publicKey,
+        newAccountPubkey: keypair.publicKey,
+        space: mintLen,
+        lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: TOKEN_2022_PROGRAM_ID
+      }),
+      createInitializeTransferHookInstruction(keypair.publicKey, extensionAuthority, hookProgram.programId, TOKEN_2022_PROGRAM_ID),
+      createInitializeMintInstruction(keypair.publicKey, 6, mintAuthority, null, TOKEN_2022_PROGRAM_ID)
+    ), [keypair]);
+
+    // a hook authority outside the program could point the mint at another hook after launch
+    const rogueMint = Keypair.generate();
+    const roguePda = (seed: string) => PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode(seed), rogueMint.publicKey.toBuffer()],
+      program.programId
+    );
+    await createHookMint(rogueMint, roguePda("authority")[0], owner.publicKey);
+    assert.equal(await errorCode(program.methods.createToken(new BN(100000 * 10 ** 6), roguePda("authority")[1]).accounts({
+      authority: roguePda("authority")[0],
+      mint: rogueMint.publicKey,
+      tokenAccountForPda: roguePda("token-account")[0],
+      tokenCreate: roguePda("token-create")[0],
+      feeAccount: user2.publicKey,
+      appStats,
+      tokenProgram: TOKEN_2022_PROGRAM_ID
+    }).rpc()), "TransferHookAuthorityNotAllowed");
+
+    await createHookMint(hookMintKeypair, hookAuthority, hookAuthority);
+    await hookProgram.methods.initializeExtraAccountMetaList().accounts({
+      payer: owner.publicKey,
+      extraAccountMetaList,
+      mint: hookMint,
+      counter
+    }).rpc();
+    await program.methods.createToken(new BN(100000 * 10 ** 6), hookBump).accounts({
+      authority: hookAuthority,
+      mint: hookMint,
+      tokenAccountForPda: hookPda("token-account"),
+      tokenCreate: hookPda("token-create"),
+      feeAccount: user2.publicKey,
+      appStats,
+      tokenProgram: TOKEN_2022_PROGRAM_ID
+    }).rpc();
+
+    const launch = () => program.methods.initializeLinearPrice(
+      new BN(1),
+      new BN(200000000),
+      new BN(150),
+      new BN(3),
+      hookBump
+    ).accounts({
+      pair: hookPda("swap-pair"),
+      observationState: hookPda("price-observation"),
+      mint: hookMint,
+      quoteMint: wsol,
+      pda: hookAuthority,
+      tokenForPda: hookPda("token-account"),
+      tokenQuoteForPda: hookPda("quote-account"),
+      tokenCreate: hookPda("token-create"),
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID,
+      tokenProgramQuote: TOKEN_PROGRAM_ID
+    }).rpc();
+    // the hook program has to be allowlisted before a mint using it can launch
+    const notAllowed = await launch().then(() => null, (e) => e);
+    assert.equal(notAllowed.error.errorCode.code, "TransferHookNotAllowed");
+    const allowTx = await program.methods.setHookProgram(true).accounts({
+      authority: owner.publicKey,
+      appStats,
+      hookProgram: hookProgram.programId,
+      governance: null,
+      proposal: null
+    }).rpc();
+    await connection.confirmTransaction(allowTx, "confirmed");
+    const [allowed] = (await cpiEvents(allowTx)).filter((event) => event.name === "HookProgramSet");
+    assert.ok(allowed.data.hookProgram.equals(hookProgram.programId));
+    assert.isTrue(allowed.data.allowed);
+    await launch();
+
+    // the hook's extra accounts are forwarded as remaining accounts
+    const tx = await program.methods.swapToTokenNative(new BN(LAMPORTS_PER_SOL), new BN(0)).accounts({
+      pda: hookAuthority,
+      mint: hookMint,
+      pair: hookPda("swap-pair"),
+      observationState: hookPda("price-observation"),
+      tokenAccountForSwapper: getAssociatedTokenAddressSync(hookMint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      tokenAccountForPda: hookPda("token-account"),
+      quoteAccountForPda: hookPda("quote-account"),
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).remainingAccounts([
+      { pubkey: extraAccountMetaList, isSigner: false, isWritable: false },
+      { pubkey: counter, isSigner: false, isWritable: true },
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false }
+    ]).rpc();
+    console.log(tx);
+    const { transfers } = await hookProgram.account.counter.fetch(counter);
+    assert.equal(transfers.toNumber(), 1);
+  });
+  it("transfer fee", async () => {
+    const feeMintKeypair = Keypair.generate();
+    const feeMint = feeMintKeypair.publicKey;
+    const feePda = (seed: string) => PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode(seed), feeMint.toBuffer()],
+      program.programId
+    )[0];
+    const [feeAuthority, feeBump] = PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("authority"), feeMint.toBuffer()],
+      program.programId
+    );
+    // 1% capped at 1000 base units, every swap here hits the cap
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    const createFeeMint = async (keypair: Keypair, mintAuthority: PublicKey, configAuthority: PublicKey) => provider.sendAndConfirm(new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: owner.publicKey,
+        newAccountPubkey: keypair.publicKey,
+        space: mintLen,
+        lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: TOKEN_2022_PROGRAM_ID
+      }),
+      createInitializeTransferFeeConfigInstruction(
+        keypair.publicKey,
+        configAuthority,
+        owner.publicKey,
+        100,
+        BigInt(1000),
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createInitializeMintInstruction(keypair.publicKey, 6, mintAuthority, null, TOKEN_2022_PROGRAM_ID)
+    ), [keypair]);
+
+    // a fee config authority outside the program could raise the fee on holders after launch
+    const rogueMint = Keypair.generate();
+    const roguePda = (seed: string) => PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode(seed), rogueMint.publicKey.toBuffer()],
+      program.programId
+    );
+    await createFeeMint(rogueMint, roguePda("authority")[0], owner.publicKey);
+    assert.equal(await errorCode(program.methods.createToken(new BN(100000 * 10 ** 6), roguePda("authority")[1]).accounts({
+      authority: roguePda("authority")[0],
+      mint: rogueMint.publicKey,
+      tokenAccountForPda: roguePda("token-account")[0],
+      tokenCreate: roguePda("token-create")[0],
+      feeAccount: user2.publicKey,
+      appStats,
+      tokenProgram: TOKEN_2022_PROGRAM_ID
+    }).rpc()), "TransferFeeAuthorityNotAllowed");
+
+    await createFeeMint(feeMintKeypair, feeAuthority, feeAuthority);
+    await program.methods.createToken(new BN(100000 * 10 ** 6), feeBump).accounts({
+      authority: feeAuthority,
+      mint: feeMint,
+      tokenAccountForPda: feePda("token-account"),
+      tokenCreate: feePda("token-create"),
+      feeAccount: user2.publicKey,
+      appStats,
+      tokenProgram: TOKEN_2022_PROGRAM_ID
+    }).rpc();
+    await program.methods.initializeLinearPrice(
+      new BN(1),
+      new BN(200000000),
+      new BN(150),
+      new BN(3),
+      feeBump
+    ).accounts({
+      pair: feePda("swap-pair"),
+      observationState: feePda("price-observation"),
+      mint: feeMint,
+      quoteMint: wsol,
+      pda: feeAuthority,
+      tokenForPda: feePda("token-account"),
+      tokenQuoteForPda: feePda("quote-account"),
+      tokenCreate: feePda("token-create"),
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID,
+      tokenProgramQuote: TOKEN_PROGRAM_ID
+    }).rpc();
+
+    const tokenAccountForSwapper = getAssociatedTokenAddressSync(
+      feeMint,
+      user1.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods.swapToTokenNative(new BN(LAMPORTS_PER_SOL / 10), new BN(1)).accounts({
+      swapper: user1.publicKey,
+      pda: feeAuthority,
+      mint: feeMint,
+      pair: feePda("swap-pair"),
+      observationState: feePda("price-observation"),
+      tokenAccountForSwapper,
+      tokenAccountForPda: feePda("token-account"),
+      quoteAccountForPda: feePda("quote-account"),
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).signers([user1]).rpc();
+    // the buy left its transfer fee withheld in user1's account
+    const bought = await getAccount(connection, tokenAccountForSwapper, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.equal(getTransferFeeAmount(bought).withheldAmount.toString(), "1000");
+
+    // selling everything harvests the withheld fee to the mint so the account can close
+    const tx = await program.methods.swapToSolNative(new BN(bought.amount.toString()), new BN(0), true).accounts({
+      swapper: user1.publicKey,
+      pda: feeAuthority,
+      mint: feeMint,
+      wsol,
+      pair: feePda("swap-pair"),
+      observationState: feePda("price-observation"),
+      tokenAccountForSwapper,
+      unwrapAccount: PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("unwrap-account"), user1.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      tokenAccountForPda: feePda("token-account"),
+      quoteAccountForPda: feePda("quote-account"),
+      feeQuoteAccount: wsolFeeAccount,
+      appStats,
+      tokenProgramMint: TOKEN_2022_PROGRAM_ID
+    }).signers([user1]).rpc();
+    console.log(tx);
+    await connection.confirmTransaction(tx, "confirmed");
+    assert.isNull(await connection.getAccountInfo(tokenAccountForSwapper, "confirmed"));
+    const mintInfo = await getMint(connection, feeMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    assert.equal(getTransferFeeConfig(mintInfo).withheldAmount.toString(), "1000");
+  });
+  it("supply policy", async () => {
+    const setPolicy = (policy) => program.methods.setSupplyPolicy(policy).accounts({
+      authority: owner.publicKey,
+      appStats,
+      governance: null,
+      proposal: null
+    }).rpc();
+    // at most 1m tokens with 6 decimals, 20% kept back for graduation liquidity
+    const policyTx = await setPolicy({
+      maxSupply: new BN(1000000 * 10 ** 6),
+      minDecimals: 6,
+      maxDecimals: 9,
+      graduationReserveBps: 2000
+    });
+    await connection.confirmTransaction(policyTx, "confirmed");
+    const [policySet] = (await cpiEvents(policyTx)).filter((event) => event.name === "SupplyPolicySet");
+    assert.equal(policySet.data.policy.maxSupply.toString(), (1000000 * 10 ** 6).toString());
+    assert.equal(policySet.data.policy.graduationReserveBps, 2000);
+    const createWith = async (supply: BN) => {
+      const policyMint = Keypair.generate();
+      const [policyAuthority, policyBump] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("authority"), policyMint.publicKey.toBuffer()],
+        program.programId
+      );
+      await createMint(
+        connection,
+        owner.payer,
+        policyAuthority,
+        null,
+        6,
+        policyMint,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      const policyTokenCreate = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("token-create"), policyMint.publicKey.toBuffer()],
+        program.programId
+      )[0];
+      await program.methods.createToken(supply, policyBump).accounts({
+        authority: policyAuthority,
+        mint: policyMint.publicKey,
+        tokenAccountForPda: PublicKey.findProgramAddressSync(
+          [anchor.utils.bytes.utf8.encode("token-account"), policyMint.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        tokenCreate: policyTokenCreate,
+        feeAccount,
+        appStats,
+        tokenProgram: TOKEN_2022_PROGRAM_ID
+      }).rpc();
+      return policyTokenCreate;
+    };
+    const overCap = await createWith(new BN(2000000 * 10 ** 6)).then(() => null, (e) => e);
+    assert.equal(overCap.error.errorCode.code, "SupplyCapExceeded");
+
+    const created = await program.account.tokenCreate.fetch(await createWith(new BN(1000000 * 10 ** 6)));
+    assert.equal(created.supply.toString(), (1000000 * 10 ** 6).toString());
+    assert.equal(created.cap.toString(), (1000000 * 10 ** 6).toString());
+    assert.equal(created.curveSupply.toString(), (800000 * 10 ** 6).toString());
+    assert.equal(created.reserveSupply.toString(), (200000 * 10 ** 6).toString());
+
+    const tx = await setPolicy({
+      maxSupply: new BN("18446744073709551615"),
+      minDecimals: 0,
+      maxDecimals: 9,
+      graduationReserveBps: 0
+    });
+    console.log(tx);
+  });
+  it("governance", async () => {
+    // enabling governance is one way, keep this as the last admin test
+    const governance = PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("governance"),
+        appStats.toBuffer()
+      ],
+      program.programId
+    )[0];
+    const signers = Array(10).fill(PublicKey.default);
+    signers[0] = owner.publicKey;
+    signers[1] = user1.publicKey;
+    await program.methods.initializeGovernance({
+      signers,
+      signerCount: 2,
+      threshold: 2,
+      timelockDelay: new BN(0)
+    }).accounts({
+      owner: owner.publicKey,
+      appStats,
+      governance
+    }).rpc();
+    const proposalAt = (id: number) => PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("proposal"),
+        governance.toBuffer(),
+        new BN(id).toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    )[0];
+    const propose = (id: number, action) => program.methods.createProposal(action).accounts({
+      proposer: owner.publicKey,
+      governance,
+      proposal: proposalAt(id)
+    }).rpc();
+    const approve = (id: number) => program.methods.approveProposal().accounts({
+      signer: user1.publicKey,
+      governance,
+      proposal: proposalAt(id)
+    }).signers([user1]).rpc();
+    const updateConfig = (params, proposal: PublicKey | null) => program.methods.updateConfig(params).accounts({
+      authority: owner.publicKey,
+      appStats,
+      governance: proposal && governance,
+      proposal
+    }).rpc();
+    const params = {
+      feeLamports: new BN(0.01 * LAMPORTS_PER_SOL),
+      feeAccount: null,
+      feeBasisPoints: null,
+      graduationThreshold: null
+    };
+    // the owner can no longer act alone
+    assert.equal(await errorCode(updateConfig(params, null)), "ProposalRequired");
+
+    // the proposer's own approval is one short of the threshold
+    await propose(0, { updateConfig: { 0: params } });
+    assert.equal(await errorCode(updateConfig(params, proposalAt(0))), "ProposalNotApproved");
+    await approve(0);
+    const tx = await updateConfig(params, proposalAt(0));
+    console.log(tx);
+    assert.equal(await errorCode(updateConfig(params, proposalAt(0))), "ProposalClosed");
+
+    // a vetoed proposal stays closed even with every approval
+    await propose(1, { updateConfig: { 0: params } });
+    await approve(1);
+    await program.methods.cancelProposal().accounts({
+      signer: user1.publicKey,
+      governance,
+      proposal: proposalAt(1)
+    }).signers([user1]).rpc();
+    assert.equal(await errorCode(updateConfig(params, proposalAt(1))), "ProposalClosed");
+
+    // changing the signer set invalidates proposals approved under the old one
+    await propose(2, { updateConfig: { 0: params } });
+    await approve(2);
+    const governanceParams = {
+      signers,
+      signerCount: 2,
+      threshold: 2,
+      timelockDelay: new BN(3600)
+    };
+    await propose(3, { updateGovernance: { 0: governanceParams } });
+    await approve(3);
+    const governanceTx = await program.methods.updateGovernance(governanceParams).accounts({
+      authority: owner.publicKey,
+      governance,
+      proposal: proposalAt(3)
+    }).rpc();
+    await connection.confirmTransaction(governanceTx, "confirmed");
+    const [governanceUpdated] = (await cpiEvents(governanceTx)).filter((event) => event.name === "GovernanceUpdated");
+    assert.equal(governanceUpdated.data.configVersion, 2);
+    assert.equal(governanceUpdated.data.params.timelockDelay.toNumber(), 3600);
+    const { configVersion, timelockDelay } = await program.account.governance.fetch(governance);
+    assert.equal(configVersion, 2);
+    assert.equal(timelockDelay.toNumber(), 3600);
+    assert.equal(await errorCode(updateConfig(params, proposalAt(2))), "StaleProposal");
+
+    // approved under the new config, but an hour away from executable
+    await propose(4, { updateConfig: { 0: params } });
+    await approve(4);
+    assert.equal(await errorCode(updateConfig(params, proposalAt(4))), "TimelockNotElapsed");
+  })
 });